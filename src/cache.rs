@@ -1,19 +1,45 @@
+use crate::output;
 use crate::types::ScanResults;
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::{SystemTime, UNIX_EPOCH};
 
 const CACHE_DIR: &str = ".cache/cleanser";
 const CACHE_FILE: &str = "last-scan.json";
-const CACHE_MAX_AGE_SECS: u64 = 3600; // 1 hour
+pub const CACHE_MAX_AGE_SECS: u64 = 3600; // 1 hour
+const HISTORY_FILE: &str = "history.json";
+const HISTORY_MAX_ENTRIES: usize = 20;
+const SNAPSHOTS_DIR: &str = "snapshots";
+/// Bump when `CachedScan` or the results in it change incompatibly, so
+/// caches written by older versions are ignored instead of misread
+pub const CACHE_FORMAT_VERSION: u32 = 1;
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct CachedScan {
+    /// `CACHE_FORMAT_VERSION` when the cache was written (0 before caches
+    /// were versioned)
+    #[serde(default)]
+    pub format_version: u32,
     pub timestamp: u64,
     pub results: ScanResults,
 }
 
+/// Totals from a past scan, kept to show trends over time
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ScanSummary {
+    pub timestamp: u64,
+    pub total_size: u64,
+    pub item_count: usize,
+}
+
+/// A scan saved under a name with `scan --save-as`
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub name: String,
+    pub summary: ScanSummary,
+}
+
 /// Get the cache file path
 fn get_cache_path() -> Result<PathBuf> {
     let home = std::env::var("HOME")?;
@@ -32,6 +58,7 @@ pub fn save_scan_results(results: &ScanResults) -> Result<()> {
     let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
     let cached = CachedScan {
+        format_version: CACHE_FORMAT_VERSION,
         timestamp,
         results: results.clone(),
     };
@@ -40,22 +67,203 @@ pub fn save_scan_results(results: &ScanResults) -> Result<()> {
     fs::write(&cache_path, json)
         .with_context(|| format!("Failed to write cache to {:?}", cache_path))?;
 
+    append_history(ScanSummary {
+        timestamp,
+        total_size: results.total_size,
+        item_count: results.items.len(),
+    })?;
+
     Ok(())
 }
 
-/// Load scan results from cache if they exist and are fresh
-pub fn load_scan_results(max_age_secs: Option<u64>) -> Result<Option<ScanResults>> {
-    let cache_path = get_cache_path()?;
+/// Check a snapshot name, rejecting names that would escape the snapshots
+/// directory
+pub fn parse_snapshot_name(name: &str) -> Result<String, String> {
+    if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+        return Err(format!("'{}' is not a valid snapshot name", name));
+    }
+    Ok(name.to_string())
+}
+
+/// Get the path of the snapshot called `name`
+fn get_snapshot_path(name: &str) -> Result<PathBuf> {
+    let name = parse_snapshot_name(name).map_err(anyhow::Error::msg)?;
+    let home = std::env::var("HOME")?;
+    Ok(PathBuf::from(home)
+        .join(CACHE_DIR)
+        .join(SNAPSHOTS_DIR)
+        .join(format!("{}.json", name)))
+}
+
+/// Save scan results as the snapshot called `name`, replacing any snapshot
+/// of the same name. Returns the snapshot's path.
+pub fn save_snapshot(name: &str, results: &ScanResults) -> Result<PathBuf> {
+    let snapshot_path = get_snapshot_path(name)?;
+
+    if let Some(parent) = snapshot_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let cached = CachedScan {
+        format_version: CACHE_FORMAT_VERSION,
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        results: results.clone(),
+    };
+
+    let json = serde_json::to_string_pretty(&cached)?;
+    fs::write(&snapshot_path, json)
+        .with_context(|| format!("Failed to write snapshot to {:?}", snapshot_path))?;
+
+    Ok(snapshot_path)
+}
+
+/// Load the snapshot called `name`, or None if there's no such snapshot
+pub fn load_snapshot(name: &str) -> Result<Option<CachedScan>> {
+    let snapshot_path = get_snapshot_path(name)?;
+
+    if !snapshot_path.exists() {
+        return Ok(None);
+    }
+
+    let contents = fs::read_to_string(&snapshot_path)
+        .with_context(|| format!("Failed to read snapshot from {:?}", snapshot_path))?;
+
+    let cached: CachedScan = serde_json::from_str(&contents)
+        .with_context(|| format!("Failed to parse snapshot '{}'", name))?;
+
+    // Unlike the cache, a snapshot is kept on purpose, so an older one is
+    // still used as far as it can be read
+    if cached.format_version != CACHE_FORMAT_VERSION {
+        output::warn(format!(
+            "snapshot '{}' was saved in format {} (current is {}); some details may be missing",
+            name, cached.format_version, CACHE_FORMAT_VERSION
+        ));
+    }
+
+    Ok(Some(cached))
+}
+
+/// List saved snapshots, oldest first. Unreadable snapshots are skipped.
+pub fn list_snapshots() -> Result<Vec<Snapshot>> {
+    let home = std::env::var("HOME")?;
+    let snapshots_dir = PathBuf::from(home).join(CACHE_DIR).join(SNAPSHOTS_DIR);
+
+    let Ok(entries) = fs::read_dir(&snapshots_dir) else {
+        return Ok(Vec::new());
+    };
+
+    let mut snapshots: Vec<Snapshot> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if path.extension()? != "json" {
+                return None;
+            }
+            let name = path.file_stem()?.to_string_lossy().into_owned();
+            let cached: CachedScan = serde_json::from_str(&fs::read_to_string(&path).ok()?).ok()?;
+            Some(Snapshot {
+                name,
+                summary: ScanSummary {
+                    timestamp: cached.timestamp,
+                    total_size: cached.results.total_size,
+                    item_count: cached.results.items.len(),
+                },
+            })
+        })
+        .collect();
+    snapshots.sort_by(|a, b| {
+        a.summary
+            .timestamp
+            .cmp(&b.summary.timestamp)
+            .then_with(|| a.name.cmp(&b.name))
+    });
 
+    Ok(snapshots)
+}
+
+/// Get the scan history file path
+fn get_history_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME")?;
+    Ok(PathBuf::from(home).join(CACHE_DIR).join(HISTORY_FILE))
+}
+
+/// Load summaries of past scans, oldest first
+pub fn load_history() -> Result<Vec<ScanSummary>> {
+    let history_path = get_history_path()?;
+
+    if !history_path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let contents = fs::read_to_string(&history_path)
+        .with_context(|| format!("Failed to read scan history from {:?}", history_path))?;
+
+    Ok(serde_json::from_str(&contents).unwrap_or_default())
+}
+
+/// Record a scan summary, keeping only the most recent entries
+fn append_history(summary: ScanSummary) -> Result<()> {
+    let history_path = get_history_path()?;
+
+    let mut history = load_history().unwrap_or_default();
+    history.push(summary);
+    if history.len() > HISTORY_MAX_ENTRIES {
+        history.drain(..history.len() - HISTORY_MAX_ENTRIES);
+    }
+
+    let json = serde_json::to_string_pretty(&history)?;
+    fs::write(&history_path, json)
+        .with_context(|| format!("Failed to write scan history to {:?}", history_path))?;
+
+    Ok(())
+}
+
+/// Read scan results saved with `scan --json`
+pub fn load_scan_file(path: &Path) -> Result<ScanResults> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read scan results from {:?}", path))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("{:?} doesn't contain `scan --json` output", path))
+}
+
+/// Load the cached scan regardless of its age. A cache that can't be parsed
+/// or was written in another format is deleted and treated as missing.
+pub fn load_cached_scan() -> Result<Option<CachedScan>> {
+    read_cached_scan(&get_cache_path()?)
+}
+
+fn read_cached_scan(cache_path: &Path) -> Result<Option<CachedScan>> {
     if !cache_path.exists() {
         return Ok(None);
     }
 
-    let contents = fs::read_to_string(&cache_path)
+    let contents = fs::read_to_string(cache_path)
         .with_context(|| format!("Failed to read cache from {:?}", cache_path))?;
 
-    let cached: CachedScan =
-        serde_json::from_str(&contents).with_context(|| "Failed to parse cached scan results")?;
+    let problem = match serde_json::from_str::<CachedScan>(&contents) {
+        Ok(cached) if cached.format_version == CACHE_FORMAT_VERSION => return Ok(Some(cached)),
+        Ok(cached) => format!(
+            "it was written in format {} (expected {})",
+            cached.format_version, CACHE_FORMAT_VERSION
+        ),
+        Err(e) => format!("it couldn't be parsed ({})", e),
+    };
+
+    output::warn(format!(
+        "ignoring the cached scan in {:?} because {}",
+        cache_path, problem
+    ));
+    // A corrupt cache would only trip every later run up too
+    let _ = fs::remove_file(cache_path);
+
+    Ok(None)
+}
+
+/// Load scan results from cache if they exist and are fresh
+pub fn load_scan_results(max_age_secs: Option<u64>) -> Result<Option<ScanResults>> {
+    let Some(cached) = load_cached_scan()? else {
+        return Ok(None);
+    };
 
     let max_age = max_age_secs.unwrap_or(CACHE_MAX_AGE_SECS);
     let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
@@ -71,7 +279,6 @@ pub fn load_scan_results(max_age_secs: Option<u64>) -> Result<Option<ScanResults
 }
 
 /// Clear the scan cache
-#[allow(dead_code)]
 pub fn clear_cache() -> Result<()> {
     let cache_path = get_cache_path()?;
 
@@ -83,19 +290,35 @@ pub fn clear_cache() -> Result<()> {
     Ok(())
 }
 
-/// Get cache age in seconds, or None if no cache exists
+/// Get cache age in seconds, or None if no usable cache exists
 pub fn get_cache_age() -> Result<Option<u64>> {
-    let cache_path = get_cache_path()?;
-
-    if !cache_path.exists() {
+    let Some(cached) = load_cached_scan()? else {
         return Ok(None);
-    }
-
-    let contents = fs::read_to_string(&cache_path)?;
-    let cached: CachedScan = serde_json::from_str(&contents)?;
+    };
 
     let current_time = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
     let age = current_time.saturating_sub(cached.timestamp);
     Ok(Some(age))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unversioned_cache_is_stale_and_removed() {
+        let dir = std::env::temp_dir().join(format!("cleanser-test-{}-cache", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join(CACHE_FILE);
+        // Written before caches carried a format version: readable, but v0
+        let contents = r#"{"timestamp": 1700000000, "results": {"items": [], "total_size": 0, "scan_speed": "normal"}}"#;
+        let parsed: CachedScan = serde_json::from_str(contents).unwrap();
+        assert_eq!(parsed.format_version, 0);
+        fs::write(&cache_path, contents).unwrap();
+
+        assert!(read_cached_scan(&cache_path).unwrap().is_none());
+        assert!(!cache_path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,177 +1,1056 @@
 use crate::types::*;
-use crate::{cache, scanner};
-use anyhow::Result;
+use crate::{cache, config, output, paths, scanner};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use dialoguer::Select;
+use globset::{Glob, GlobSetBuilder};
 use humansize::{format_size, BINARY};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
+use std::io::{self, IsTerminal};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
 
-fn run_fresh_scan() -> Result<ScanResults> {
+/// The paths `clean` scans when it can't reuse a cached scan
+fn default_scan_paths() -> Result<Vec<String>> {
+    Ok(vec![std::env::var("HOME")?])
+}
+
+pub fn run_fresh_scan() -> Result<ScanResults> {
     let config = ScanConfig {
         speed: ScanSpeed::Normal,
-        paths: vec![std::env::var("HOME")?],
+        paths: default_scan_paths()?,
         min_file_size_mb: 0, // Don't scan for large files during clean
+        extensions: Vec::new(),
+        excluded_extensions: Vec::new(),
+        min_dir_size_mb: 1,
+        log_min_size_mb: 10,
         max_depth: Some(6),
         find_duplicates: false, // Don't look for duplicates during clean
+        dup_min_size_kb: 1024,
+        hash_threads: None,
+        follow_symlinks: false,
+        estimate: false,
+        apparent_size: false,
+        dereference_size: false,
+        strict_paths: false,
+        enabled_phases: ScanPhase::all(),
+        skip_dirty_repos: false,
+        repo_activity: false,
+        custom_rules: config::load_config()?.rules,
+        previous: None,
     };
 
-    let results = scanner::scan(config)?;
+    let spinner = output::Spinner::new();
+    let results = scanner::scan(config, Some(&|event| spinner.handle(event)))?;
 
     // Save to cache for next time
     if let Err(e) = cache::save_scan_results(&results) {
-        eprintln!(
-            "{}",
-            format!("Warning: Failed to save scan cache: {}", e).yellow()
-        );
+        output::warn(format!("Failed to save scan cache: {}", e));
     }
 
     Ok(results)
 }
 
-pub fn clean(max_risk: RiskLevel, dry_run: bool, force_scan: bool) -> Result<()> {
+pub fn clean(config: CleanConfig) -> Result<CleanReport> {
     // Try to load from cache first
-    let results = if !force_scan {
-        match cache::load_scan_results(None) {
+    let results = if let Some(path) = &config.load {
+        load_scan_file(path, config.cache_max_age)?
+    } else if !config.force_scan {
+        match cache::load_scan_results(config.cache_max_age) {
             Ok(Some(cached_results)) => {
                 if let Ok(Some(age)) = cache::get_cache_age() {
                     let mins = age / 60;
                     let secs = age % 60;
                     if mins > 0 {
-                        println!(
-                            "{}",
+                        output::status(
                             format!(
                                 "Using cached scan results from {} min {} sec ago",
                                 mins, secs
                             )
-                            .cyan()
+                            .cyan(),
                         );
                     } else {
-                        println!(
-                            "{}",
-                            format!("Using cached scan results from {} seconds ago", secs).cyan()
+                        output::status(
+                            format!("Using cached scan results from {} seconds ago", secs).cyan(),
                         );
                     }
-                    println!("{}", "Tip: Use --force-scan to run a fresh scan".dimmed());
+                    if !cached_results.scanned_paths.is_empty() {
+                        output::status(
+                            format!(
+                                "Cached scan covers: {}",
+                                cached_results.scanned_paths.join(", ")
+                            )
+                            .cyan(),
+                        );
+                    }
+                    output::status("Tip: Use --force-scan to run a fresh scan".dimmed());
                 }
+                warn_on_path_mismatch(&cached_results.scanned_paths)?;
                 cached_results
             }
             Ok(None) => {
-                println!("{}", "No cached scan found, running fresh scan...".cyan());
+                output::status("No recent cached scan found, running fresh scan...".cyan());
                 run_fresh_scan()?
             }
             Err(e) => {
-                println!(
-                    "{}",
-                    format!("Failed to load cache ({}), running fresh scan...", e).yellow()
+                output::status(
+                    format!("Failed to load cache ({}), running fresh scan...", e).yellow(),
                 );
                 run_fresh_scan()?
             }
         }
     } else {
-        println!("{}", "Running fresh scan (--force-scan)...".cyan());
+        output::status("Running fresh scan (--force-scan)...".cyan());
         run_fresh_scan()?
     };
 
-    // Filter items by risk level
-    let items_to_clean: Vec<&CleanableItem> = results
+    let mut max_risk = config.max_risk;
+    if config.on_battery_safe_only && max_risk > RiskLevel::Safe {
+        match on_battery_power() {
+            Some(true) => {
+                output::status(
+                    "On battery power: only cleaning Safe items (--on-battery-safe-only)"
+                        .yellow()
+                        .bold(),
+                );
+                max_risk = RiskLevel::Safe;
+            }
+            Some(false) => {}
+            None => output::status(
+                format!(
+                    "Couldn't tell whether this Mac is on battery; cleaning up to {} as requested",
+                    max_risk
+                )
+                .yellow(),
+            ),
+        }
+    }
+
+    // A path that matches nothing is more likely a typo or a stale scan than
+    // a request to clean nothing
+    let under_paths = |item: &CleanableItem| {
+        config.paths.is_empty()
+            || config
+                .paths
+                .iter()
+                .any(|path| Path::new(&item.path).starts_with(path))
+    };
+    for path in &config.paths {
+        if !results
+            .items
+            .iter()
+            .any(|item| Path::new(&item.path).starts_with(path))
+        {
+            bail!(
+                "No scanned item at or under {} (run `cleanser scan` to refresh the results)",
+                path.display()
+            );
+        }
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in &config.patterns {
+        builder.add(
+            Glob::new(&paths::expand_path(pattern))
+                .with_context(|| format!("Invalid --match pattern '{}'", pattern))?,
+        );
+    }
+    let globs = builder.build()?;
+    let matches_patterns = |item: &CleanableItem| {
+        config.patterns.is_empty()
+            || Path::new(&item.path)
+                .ancestors()
+                .any(|path| globs.is_match(path))
+    };
+
+    // Filter items by risk level, pattern, category and path. Sorting by path
+    // keeps the listing (and the order of deletion) the same from run to run
+    // whatever order the scan found things in, so dry runs can be diffed.
+    let mut items_to_clean: Vec<&CleanableItem> = results
+        .filter_by_risk(max_risk)
+        .into_iter()
+        .filter(|item| matches_patterns(item))
+        .filter(|item| config.categories.is_empty() || config.categories.contains(&item.category))
+        .filter(|item| under_paths(item))
+        .collect();
+    items_to_clean.sort_by(|a, b| a.path.cmp(&b.path));
+    let above_risk = results
         .items
         .iter()
-        .filter(|item| item.risk_level <= max_risk)
-        .collect();
+        .filter(|item| item.risk_level > max_risk && under_paths(item))
+        .count();
+    if !config.paths.is_empty() && above_risk > 0 {
+        output::status(
+            format!(
+                "Skipping {} items under the given paths rated above {} risk (use --risk to include them)",
+                above_risk, max_risk
+            )
+            .yellow(),
+        );
+    }
+
+    // Refuse to touch critical locations unless explicitly overridden
+    let (items_to_clean, protected): (Vec<&CleanableItem>, Vec<&CleanableItem>) =
+        if config.allow_critical {
+            (items_to_clean, Vec::new())
+        } else {
+            items_to_clean
+                .into_iter()
+                .partition(|item| !is_protected(item))
+        };
+    for item in &protected {
+        output::alert(format!(
+            "Refusing to delete critical path {} (use --i-know-what-im-doing to override)",
+            item.path
+        ));
+    }
+    let protected: Vec<String> = protected.iter().map(|item| item.path.clone()).collect();
+
+    // A whole Photos or Final Cut library is only ever deleted one at a time
+    let (items_to_clean, libraries): (Vec<&CleanableItem>, Vec<&CleanableItem>) = items_to_clean
+        .into_iter()
+        .partition(|item| config.confirm_each || item.category != CleanCategory::MediaLibraries);
+    if !libraries.is_empty() {
+        output::status(
+            format!(
+                "Skipping {} media libraries (use --confirm-each to decide on each)",
+                libraries.len()
+            )
+            .yellow(),
+        );
+    }
+
+    // Outdated Homebrew versions go through brew, or not at all
+    let (brew_managed, items_to_clean): (Vec<&CleanableItem>, Vec<&CleanableItem>) =
+        if config.apply_brew_cleanup {
+            (Vec::new(), items_to_clean)
+        } else {
+            items_to_clean
+                .into_iter()
+                .partition(|item| is_brew_managed(item))
+        };
+    if !brew_managed.is_empty() {
+        output::status(
+            format!(
+                "Skipping {} outdated Homebrew versions (use --apply-brew-cleanup to remove them with `brew cleanup`)",
+                brew_managed.len()
+            )
+            .yellow(),
+        );
+    }
 
     if items_to_clean.is_empty() {
-        println!("{}", "No items found to clean.".yellow());
-        return Ok(());
+        output::status("No items found to clean.".yellow());
+        return Ok(CleanReport {
+            protected,
+            ..CleanReport::default()
+        });
     }
 
     let total_size: u64 = items_to_clean.iter().map(|item| item.size).sum();
 
-    println!("\n{}", "=== Items to Clean ===".green().bold());
-    println!(
-        "Total space to free: {}\n",
-        format_size(total_size, BINARY).bold()
-    );
-
-    for item in &items_to_clean {
-        let risk_indicator = match item.risk_level {
-            RiskLevel::Safe => "✓".green(),
-            RiskLevel::Moderate => "⚠".yellow(),
-            RiskLevel::Risky => "⚠".red(),
-        };
+    if !config.json {
+        print_items(&items_to_clean, total_size);
+    }
 
-        println!(
-            "{} {} - {} - {}",
-            risk_indicator,
-            item.category,
-            format_size(item.size, BINARY),
-            item.path.dimmed()
-        );
+    if config.dry_run {
+        output::status("DRY RUN: No files were deleted.".yellow().bold());
+        return Ok(CleanReport {
+            protected,
+            ..CleanReport::default()
+        });
     }
 
-    println!();
+    if config.confirm_each && !io::stderr().is_terminal() {
+        bail!("--confirm-each needs an interactive terminal");
+    }
 
-    if dry_run {
-        println!("{}", "DRY RUN: No files were deleted.".yellow().bold());
-        return Ok(());
+    // Small cleanups under --confirm-threshold go ahead without asking
+    let would_prompt = !config.yes && !config.confirm_each;
+    match config.confirm_threshold {
+        Some(threshold) if would_prompt && total_size < threshold => output::status(
+            format!(
+                "Less than {} to free, cleaning without asking (--confirm-threshold)",
+                format_size(threshold, BINARY)
+            )
+            .cyan(),
+        ),
+        _ if would_prompt && !confirm(total_size, items_to_clean.len(), max_risk)? => {
+            output::status("Cancelled.");
+            return Ok(CleanReport {
+                protected,
+                ..CleanReport::default()
+            });
+        }
+        _ => {}
     }
 
     // Perform the cleanup
-    let mut cleaned_size = 0u64;
-    let mut cleaned_count = 0usize;
-    let mut failed_count = 0usize;
+    let mut report = CleanReport {
+        protected,
+        ..CleanReport::default()
+    };
+    let free_before = free_space_by_volume(&items_to_clean);
+
+    // Categories the user chose to delete without further prompts
+    let mut approved: HashSet<CleanCategory> = HashSet::new();
+
+    // Collected and handed to a single `brew cleanup` at the end
+    let mut brew_items: Vec<&CleanableItem> = Vec::new();
 
     for item in items_to_clean {
-        match delete_item(&item.path) {
-            Ok(size) => {
-                cleaned_size += size;
-                cleaned_count += 1;
+        if config.confirm_each && !approved.contains(&item.category) {
+            match prompt_item(item)? {
+                ItemChoice::Delete => {}
+                ItemChoice::Skip => continue,
+                ItemChoice::DeleteCategory => {
+                    approved.insert(item.category.clone());
+                }
+                ItemChoice::Quit => break,
+            }
+        }
+
+        if is_brew_managed(item) {
+            brew_items.push(item);
+        } else {
+            clean_item(item, config.sudo, config.json, &mut report);
+        }
+    }
+
+    if !brew_items.is_empty() {
+        brew_cleanup(&brew_items, config.json, &mut report);
+    }
+    report.volumes = volume_space(free_before);
+
+    if !config.json {
+        print_summary(&report);
+    }
+
+    Ok(report)
+}
+
+/// Whether an item is a Homebrew package version, which only `brew cleanup`
+/// may remove without leaving Homebrew's records out of step
+fn is_brew_managed(item: &CleanableItem) -> bool {
+    item.category == CleanCategory::BrewCache
+        && Path::new(&item.path)
+            .components()
+            .any(|c| c.as_os_str() == "Cellar" || c.as_os_str() == "Caskroom")
+}
+
+/// Remove outdated Homebrew versions by running `brew cleanup` on their
+/// formulae and casks, and show the space brew reports it freed
+fn brew_cleanup(items: &[&CleanableItem], json: bool, report: &mut CleanReport) {
+    // Versions live at <prefix>/Cellar/<formula>/<version>
+    let names: BTreeSet<String> = items
+        .iter()
+        .filter_map(|item| Path::new(&item.path).parent()?.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+        .collect();
+
+    let measure = || -> u64 {
+        items
+            .iter()
+            .map(|item| path_size(Path::new(&item.path)))
+            .sum()
+    };
+    let before = measure();
+
+    let result = Command::new("brew")
+        .arg("cleanup")
+        .args(&names)
+        .env("HOMEBREW_NO_AUTO_UPDATE", "1")
+        .env("HOMEBREW_NO_ENV_HINTS", "1")
+        .output()
+        .map_err(anyhow::Error::from)
+        .and_then(|output| {
+            if output.status.success() {
+                Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+            } else {
+                bail!(
+                    "brew cleanup failed ({}): {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )
+            }
+        });
+
+    // Measured like any other deletion; brew's own figure is only approximate
+    report.expected_space_freed += before;
+    report.space_freed += before.saturating_sub(measure());
+
+    match result {
+        Ok(stdout) => {
+            // "==> This operation has freed approximately 1.2GB of disk space."
+            let freed = stdout
+                .lines()
+                .find_map(|line| line.split("freed approximately ").nth(1))
+                .and_then(|rest| rest.split_whitespace().next())
+                .and_then(|size| config::parse_size(size).ok())
+                .unwrap_or(0);
+            report.cleaned += items.len();
+            if !json {
+                for item in items {
+                    println!("{} Cleaned: {}", "✓".green(), item.path.dimmed());
+                }
+                println!(
+                    "{} brew cleanup freed {}",
+                    "✓".green(),
+                    format_size(freed, BINARY)
+                );
+            }
+        }
+        Err(e) => {
+            for item in items {
+                report.failed += 1;
+                if !json {
+                    println!("{} Failed to clean {}: {}", "✗".red(), item.path, e);
+                }
+                report.failures.push(CleanFailure {
+                    path: item.path.clone(),
+                    error: e.to_string(),
+                    residual_size: path_size(Path::new(&item.path)),
+                });
+            }
+        }
+    }
+}
+
+/// Delete a set of hand-picked items, such as those marked in `review`.
+/// Critical paths are refused just as `clean` refuses them, and outdated
+/// Homebrew versions are left to `brew cleanup`.
+pub fn clean_selected(items: &[&CleanableItem], sudo: bool) -> CleanReport {
+    let mut report = CleanReport::default();
+    let mut brew_items: Vec<&CleanableItem> = Vec::new();
+    let free_before = free_space_by_volume(items);
+
+    for item in items {
+        if is_brew_managed(item) {
+            brew_items.push(item);
+            continue;
+        }
+        if is_protected(item) {
+            output::alert(format!("Refusing to delete critical path {}", item.path));
+            report.protected.push(item.path.clone());
+            continue;
+        }
+        clean_item(item, sudo, false, &mut report);
+    }
+
+    if !brew_items.is_empty() {
+        brew_cleanup(&brew_items, false, &mut report);
+    }
+    report.volumes = volume_space(free_before);
+
+    print_summary(&report);
+    report
+}
+
+/// Delete one item and record the outcome in `report`
+fn clean_item(item: &CleanableItem, sudo: bool, json: bool, report: &mut CleanReport) {
+    if !item.members.is_empty() {
+        clean_members(item, sudo, json, report);
+        return;
+    }
+
+    let path = &paths::canonicalize_parent(Path::new(&item.path));
+
+    // Measure before and after so the report counts what actually went
+    let before = path_size(path);
+    let result = if item.category == CleanCategory::Trash {
+        empty_directory(path, sudo)
+    } else {
+        delete_item(path, sudo)
+    };
+    let residual = path_size(path);
+
+    report.expected_space_freed += before;
+    report.space_freed += before.saturating_sub(residual);
+
+    match result {
+        Ok(()) => {
+            report.cleaned += 1;
+            if !json {
                 println!("{} Cleaned: {}", "✓".green(), item.path.dimmed());
             }
-            Err(e) => {
-                failed_count += 1;
-                println!("{} Failed to clean {}: {}", "✗".red(), item.path, e);
+        }
+        Err(e) => {
+            report.failed += 1;
+            if !json {
+                let left = if residual > 0 && residual < before {
+                    format!(" ({} still present)", format_size(residual, BINARY))
+                } else {
+                    String::new()
+                };
+                println!("{} Failed to clean {}: {}{}", "✗".red(), item.path, e, left);
+            }
+            report.failures.push(CleanFailure {
+                path: item.path.clone(),
+                error: e.to_string(),
+                residual_size: residual,
+            });
+        }
+    }
+}
+
+/// Delete each file an aggregated item stands for. Files that are already
+/// gone are skipped; the item only counts as cleaned if no deletion failed.
+fn clean_members(item: &CleanableItem, sudo: bool, json: bool, report: &mut CleanReport) {
+    let mut failures = 0;
+
+    for member in &item.members {
+        let path = &paths::canonicalize_parent(Path::new(member));
+        if fs::symlink_metadata(path).is_err() {
+            continue;
+        }
+
+        let before = path_size(path);
+        let result = delete_item(path, sudo);
+        let residual = path_size(path);
+
+        report.expected_space_freed += before;
+        report.space_freed += before.saturating_sub(residual);
+
+        if let Err(e) = result {
+            failures += 1;
+            if !json {
+                println!("{} Failed to delete {}: {}", "✗".red(), member, e);
             }
+            report.failures.push(CleanFailure {
+                path: member.clone(),
+                error: e.to_string(),
+                residual_size: residual,
+            });
         }
     }
 
+    if failures == 0 {
+        report.cleaned += 1;
+        if !json {
+            println!(
+                "{} Cleaned: {} ({} paths)",
+                "✓".green(),
+                item.path.dimmed(),
+                item.members.len()
+            );
+        }
+    } else {
+        report.failed += 1;
+        if !json {
+            println!(
+                "{} Failed to clean {} of {} paths in {}",
+                "✗".red(),
+                failures,
+                item.members.len(),
+                item.path
+            );
+        }
+    }
+}
+
+/// Whether the Mac is running on battery (or UPS) power, per `pmset -g batt`.
+/// None when the power source can't be determined.
+fn on_battery_power() -> Option<bool> {
+    let output = Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // The first line reads e.g. "Now drawing from 'Battery Power'"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let source = stdout.lines().next()?.split('\'').nth(1)?;
+    match source {
+        "AC Power" => Some(false),
+        "Battery Power" | "UPS Power" => Some(true),
+        _ => None,
+    }
+}
+
+/// Walk through groups of identical files, asking which copy of each to keep
+/// and deleting the others
+pub fn resolve_duplicates(
+    groups: &[DuplicateGroup],
+    dry_run: bool,
+    sudo: bool,
+) -> Result<CleanReport> {
+    if groups.is_empty() {
+        output::status(
+            "No duplicates in the latest scan (run `cleanser scan --find-duplicates` first)"
+                .yellow(),
+        );
+        return Ok(CleanReport::default());
+    }
+
+    if !io::stderr().is_terminal() {
+        bail!("Choosing which duplicates to keep needs an interactive terminal");
+    }
+
+    let mut report = CleanReport::default();
+
+    for (index, group) in groups.iter().enumerate() {
+        let mut choices: Vec<String> = group
+            .paths
+            .iter()
+            .map(|path| format!("Keep {}", path))
+            .collect();
+        choices.push("Skip this group".to_string());
+        choices.push("Quit".to_string());
+
+        let selection = Select::new()
+            .with_prompt(format!(
+                "[{}/{}] {} identical copies of {}",
+                index + 1,
+                groups.len(),
+                group.paths.len(),
+                format_size(group.size, BINARY)
+            ))
+            .items(&choices)
+            .default(group.paths.len())
+            .interact()?;
+
+        if selection == group.paths.len() {
+            continue;
+        }
+        if selection > group.paths.len() {
+            break;
+        }
+
+        for (i, copy) in group.paths.iter().enumerate() {
+            if i == selection {
+                continue;
+            }
+            if dry_run {
+                println!("{} Would delete: {}", "-".yellow(), copy.dimmed());
+                continue;
+            }
+
+            let path = &paths::canonicalize_parent(Path::new(copy));
+            let before = path_size(path);
+            let result = delete_item(path, sudo);
+            let residual = path_size(path);
+
+            report.expected_space_freed += before;
+            report.space_freed += before.saturating_sub(residual);
+
+            match result {
+                Ok(()) => {
+                    report.cleaned += 1;
+                    println!("{} Deleted: {}", "✓".green(), copy.dimmed());
+                }
+                Err(e) => {
+                    report.failed += 1;
+                    println!("{} Failed to delete {}: {}", "✗".red(), copy, e);
+                    report.failures.push(CleanFailure {
+                        path: copy.clone(),
+                        error: e.to_string(),
+                        residual_size: residual,
+                    });
+                }
+            }
+        }
+    }
+
+    if dry_run {
+        output::status("DRY RUN: No files were deleted.".yellow().bold());
+    } else {
+        print_summary(&report);
+    }
+
+    Ok(report)
+}
+
+fn print_summary(report: &CleanReport) {
     println!("\n{}", "=== Cleanup Summary ===".green().bold());
     println!(
         "Cleaned: {} items",
-        cleaned_count.to_string().green().bold()
+        report.cleaned.to_string().green().bold()
     );
-    println!("Failed: {} items", failed_count.to_string().red().bold());
+    println!("Failed: {} items", report.failed.to_string().red().bold());
     println!(
         "Space freed: {}",
-        format_size(cleaned_size, BINARY).green().bold()
+        format_size(report.space_freed, BINARY).green().bold()
     );
+    if report.space_freed != report.expected_space_freed {
+        println!(
+            "{}",
+            format!(
+                "Expected to free {}, but {} is still present",
+                format_size(report.expected_space_freed, BINARY),
+                format_size(report.expected_space_freed - report.space_freed, BINARY)
+            )
+            .yellow()
+        );
+    }
+
+    // What the filesystem says, which can differ from the sizes above when
+    // files were compressed, are held by a snapshot or something else wrote
+    for volume in &report.volumes {
+        let change = if volume.free_after >= volume.free_before {
+            format!(
+                "+{}",
+                format_size(volume.free_after - volume.free_before, BINARY)
+            )
+        } else {
+            format!(
+                "-{}",
+                format_size(volume.free_before - volume.free_after, BINARY)
+            )
+        };
+        let label = if report.volumes.len() == 1 {
+            "Free space".to_string()
+        } else {
+            format!("Free space on {}", volume.mount_point)
+        };
+        println!(
+            "{}: {} → {} ({})",
+            label,
+            format_size(volume.free_before, BINARY),
+            format_size(volume.free_after, BINARY),
+            change.bold()
+        );
+    }
+}
+
+/// Free space on each volume holding one of `items`, keyed by device, with
+/// the volume's mount point
+fn free_space_by_volume(items: &[&CleanableItem]) -> BTreeMap<u64, (PathBuf, u64)> {
+    let mut volumes = BTreeMap::new();
+    for target in items.iter().flat_map(|item| item.targets()) {
+        let path = Path::new(target);
+        let Ok(metadata) = fs::symlink_metadata(path) else {
+            continue;
+        };
+        if volumes.contains_key(&metadata.dev()) {
+            continue;
+        }
+        if let Some(free) = free_space(path) {
+            volumes.insert(metadata.dev(), (mount_point(path, metadata.dev()), free));
+        }
+    }
+    volumes
+}
+
+/// Pair the free space measured before a clean with what's free now
+fn volume_space(free_before: BTreeMap<u64, (PathBuf, u64)>) -> Vec<VolumeSpace> {
+    free_before
+        .into_values()
+        .map(|(mount_point, before)| VolumeSpace {
+            free_after: free_space(&mount_point).unwrap_or(before),
+            mount_point: mount_point.display().to_string(),
+            free_before: before,
+        })
+        .collect()
+}
+
+/// The outermost directory above `path` on the same device
+fn mount_point(path: &Path, dev: u64) -> PathBuf {
+    path.ancestors()
+        .take_while(|dir| fs::metadata(dir).is_ok_and(|metadata| metadata.dev() == dev))
+        .last()
+        .unwrap_or(path)
+        .to_path_buf()
+}
+
+/// Bytes available to unprivileged users on the volume holding `path`
+fn free_space(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: statvfs is plain data and is only read after a successful call
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
+
+/// Load the scan results passed to `--load`, warning when they're older than
+/// a cached scan `clean` would reuse
+fn load_scan_file(path: &Path, max_age_secs: Option<u64>) -> Result<ScanResults> {
+    let results = cache::load_scan_file(path)?;
+    output::status(
+        format!(
+            "Using scan results from {} ({} items)",
+            path.display(),
+            results.items.len()
+        )
+        .cyan(),
+    );
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let age = now.saturating_sub(results.timestamp);
+    if results.timestamp == 0 {
+        output::warn(format!(
+            "{} doesn't record when the scan ran; files may have changed since",
+            path.display()
+        ));
+    } else if age > max_age_secs.unwrap_or(cache::CACHE_MAX_AGE_SECS) {
+        let age = if age < 2 * 86400 {
+            format!("{} hours", age / 3600)
+        } else {
+            format!("{} days", age / 86400)
+        };
+        output::warn(format!(
+            "the scan in {} ran {} ago; files may have changed since",
+            path.display(),
+            age
+        ));
+    }
+
+    Ok(results)
+}
+
+/// Warn loudly when a cached scan covered different paths than a fresh scan
+/// would. Scans from before paths were recorded can't be checked.
+fn warn_on_path_mismatch(cached_paths: &[String]) -> Result<()> {
+    if cached_paths.is_empty() {
+        return Ok(());
+    }
+
+    let normalize = |paths: &[String]| -> BTreeSet<PathBuf> {
+        paths.iter().map(|p| paths::normalize_path(p)).collect()
+    };
+    let cached = normalize(cached_paths);
+    let current = normalize(&default_scan_paths()?);
+
+    if cached != current {
+        let list = |paths: &BTreeSet<PathBuf>| {
+            paths
+                .iter()
+                .map(|p| p.display().to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        output::warn(format!(
+            "the cached scan covered {} but clean would scan {}; \
+             run with --force-scan to clean based on a fresh scan",
+            list(&cached),
+            list(&current)
+        ));
+    }
 
     Ok(())
 }
 
-fn delete_item(path: &str) -> Result<u64> {
-    let path = std::path::Path::new(path);
+fn print_items(items: &[&CleanableItem], total_size: u64) {
+    println!("\n{}", "=== Items to Clean ===".green().bold());
+    println!(
+        "Total space to free: {}\n",
+        format_size(total_size, BINARY).bold()
+    );
 
-    if !path.exists() {
-        return Ok(0);
+    for item in items {
+        let risk_indicator = match item.risk_level {
+            RiskLevel::Safe => "✓".green(),
+            RiskLevel::Moderate => "⚠".yellow(),
+            RiskLevel::Risky => "⚠".red(),
+            RiskLevel::Manual => "⚠".magenta(),
+        };
+
+        let files = if item.members.is_empty() {
+            String::new()
+        } else {
+            format!(" ({} paths)", item.members.len())
+        };
+        println!(
+            "{} {} - {} - {}{}",
+            risk_indicator,
+            item.category,
+            scanner::format_item_size(item),
+            item.path.dimmed(),
+            files.dimmed()
+        );
     }
 
-    // Calculate size before deletion
-    let size = if path.is_dir() {
-        get_dir_size_fast(path)?
+    println!();
+}
+
+/// Ask the user to confirm the deletion. Risky cleans require typing "delete".
+fn confirm(total_size: u64, count: usize, max_risk: RiskLevel) -> Result<bool> {
+    let summary = format!(
+        "This will permanently delete {} items ({}).",
+        count,
+        format_size(total_size, BINARY)
+    );
+    eprintln!("{}", summary.yellow().bold());
+
+    let mut input = String::new();
+    if max_risk >= RiskLevel::Risky {
+        eprintln!("{}", "Type 'delete' to continue:".red().bold());
+        std::io::stdin().read_line(&mut input)?;
+        Ok(input.trim() == "delete")
     } else {
-        fs::metadata(path)?.len()
+        eprintln!("{}", "Continue? (y/N)".yellow());
+        std::io::stdin().read_line(&mut input)?;
+        Ok(input.trim().eq_ignore_ascii_case("y"))
+    }
+}
+
+/// Locations that must never be deleted, nor anything containing them
+const CRITICAL_PATHS: &[&str] = &[
+    "/",
+    "/Applications",
+    "/Library",
+    "/System",
+    "/Users",
+    "/bin",
+    "/etc",
+    "/opt",
+    "/private",
+    "/sbin",
+    "/usr",
+    "/var",
+    "/Volumes",
+];
+
+/// Home folder entries that must never be deleted, nor anything inside them
+const CRITICAL_HOME_PATHS: &[&str] = &[".ssh", ".gnupg", "Library/Keychains"];
+
+/// Whether deleting an item would remove a critical path (any of its
+/// members, for an aggregated item)
+fn is_protected(item: &CleanableItem) -> bool {
+    item.targets()
+        .into_iter()
+        .any(|path| is_critical_path(Path::new(path)))
+}
+
+/// Whether deleting `path` would remove a critical location: `/`, the home
+/// folder, a system directory, or credentials such as `~/.ssh`. Paths are
+/// resolved first so symlinks and `..` can't disguise them.
+fn is_critical_path(path: &Path) -> bool {
+    let resolve = |p: &Path| fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+    let path = resolve(path);
+
+    let home = std::env::var("HOME")
+        .ok()
+        .map(|home| resolve(Path::new(&home)));
+
+    let mut critical: Vec<PathBuf> = CRITICAL_PATHS
+        .iter()
+        .map(|p| resolve(Path::new(p)))
+        .collect();
+    critical.extend(home.clone());
+
+    // Deleting a critical directory or any of its ancestors
+    if critical.iter().any(|dir| dir.starts_with(&path)) {
+        return true;
+    }
+
+    // Deleting anything inside credential stores or the core OS
+    let mut sealed: Vec<PathBuf> = ["/System", "/bin", "/sbin", "/usr/bin", "/usr/lib"]
+        .iter()
+        .map(|p| resolve(Path::new(p)))
+        .collect();
+    if let Some(home) = &home {
+        sealed.extend(CRITICAL_HOME_PATHS.iter().map(|p| resolve(&home.join(p))));
+    }
+
+    sealed.iter().any(|dir| path.starts_with(dir))
+}
+
+/// Answer to a `--confirm-each` prompt
+enum ItemChoice {
+    Delete,
+    Skip,
+    DeleteCategory,
+    Quit,
+}
+
+/// Ask what to do with a single item
+fn prompt_item(item: &CleanableItem) -> Result<ItemChoice> {
+    let choices = [
+        "Delete",
+        "Skip",
+        "Delete all remaining in this category",
+        "Quit",
+    ];
+    let selection = Select::new()
+        .with_prompt(format!(
+            "{} - {} - {}",
+            item.category,
+            scanner::format_item_size(item),
+            item.path
+        ))
+        .items(choices)
+        .default(1)
+        .interact()?;
+
+    Ok(match selection {
+        0 => ItemChoice::Delete,
+        2 => ItemChoice::DeleteCategory,
+        3 => ItemChoice::Quit,
+        _ => ItemChoice::Skip,
+    })
+}
+
+fn delete_item(path: &Path, sudo: bool) -> Result<()> {
+    // Never follow a symlink: removing one only unlinks the link itself,
+    // leaving its target untouched
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return Ok(());
     };
 
     // Delete the item
-    if path.is_dir() {
-        fs::remove_dir_all(path)?;
+    let removal = if metadata.is_dir() {
+        fs::remove_dir_all(path)
     } else {
-        fs::remove_file(path)?;
+        fs::remove_file(path)
+    };
+
+    match removal {
+        Ok(()) => {}
+        Err(e) if sudo && e.kind() == io::ErrorKind::PermissionDenied => sudo_remove(path)?,
+        Err(e) => return Err(e.into()),
     }
 
-    Ok(size)
+    Ok(())
 }
 
-fn get_dir_size_fast(path: &std::path::Path) -> Result<u64> {
+/// Remove a path with an elevated `rm`, letting sudo prompt for the password.
+/// The path is passed as a plain argument, never through a shell.
+fn sudo_remove(path: &Path) -> Result<()> {
+    let status = Command::new("sudo")
+        .arg("rm")
+        .arg("-rf")
+        .arg("--")
+        .arg(path)
+        .status()?;
+
+    if !status.success() {
+        bail!("sudo rm failed ({})", status);
+    }
+
+    Ok(())
+}
+
+/// Delete everything inside a directory while keeping the directory itself
+fn empty_directory(path: &Path, sudo: bool) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    for entry in fs::read_dir(path)? {
+        delete_item(&entry?.path(), sudo)?;
+    }
+
+    Ok(())
+}
+
+/// Disk space currently used at a path (0 once it's gone)
+fn path_size(path: &Path) -> u64 {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => get_dir_size_fast(path),
+        Ok(metadata) => metadata.blocks() * 512,
+        Err(_) => 0,
+    }
+}
+
+fn get_dir_size_fast(path: &Path) -> u64 {
     let mut total = 0;
 
     for entry in walkdir::WalkDir::new(path)
@@ -181,10 +1060,36 @@ fn get_dir_size_fast(path: &std::path::Path) -> Result<u64> {
     {
         if entry.file_type().is_file() {
             if let Ok(metadata) = entry.metadata() {
-                total += metadata.len();
+                total += metadata.blocks() * 512;
             }
         }
     }
 
-    Ok(total)
+    total
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deleting_a_symlink_to_a_directory_keeps_the_target() {
+        let dir =
+            std::env::temp_dir().join(format!("cleanser-test-{}-symlink", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let target = dir.join("target");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("keep.txt"), "still here").unwrap();
+        let link = dir.join("link");
+        std::os::unix::fs::symlink(&target, &link).unwrap();
+
+        delete_item(&link, false).unwrap();
+
+        assert!(fs::symlink_metadata(&link).is_err());
+        assert_eq!(
+            fs::read_to_string(target.join("keep.txt")).unwrap(),
+            "still here"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
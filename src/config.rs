@@ -0,0 +1,116 @@
+use crate::types::CustomRule;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+const CONFIG_DIR: &str = ".config/cleanser";
+const CONFIG_FILE: &str = "config.toml";
+
+/// Settings read from `~/.config/cleanser/config.toml`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Custom detectors, one `[[rules]]` table each
+    pub rules: Vec<CustomRule>,
+    /// How old a cached scan `clean` may reuse, e.g. `"30m"` or `"1d"`
+    pub cache_max_age: Option<String>,
+}
+
+impl Config {
+    /// The configured cache max age in seconds
+    pub fn cache_max_age_secs(&self) -> Result<Option<u64>> {
+        self.cache_max_age
+            .as_deref()
+            .map(|age| parse_duration(age).map_err(|e| anyhow!("Invalid cache_max_age: {}", e)))
+            .transpose()
+    }
+}
+
+/// Parse a duration such as `90`, `90s`, `10m`, `2h` or `1d` into seconds
+pub fn parse_duration(duration: &str) -> Result<u64, String> {
+    let duration = duration.trim();
+    let split = duration
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(duration.len());
+    let (number, unit) = duration.split_at(split);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a duration like 30m, 2h or 1d", duration))?;
+    let multiplier = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return Err(format!("unknown unit '{}' (expected s, m, h or d)", unit)),
+    };
+
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("'{}' is too long a duration", duration))
+}
+
+/// Parse a size such as `500M`, `2G` or `1.5GB` into bytes. Units are binary
+/// (K, M, G, T, optionally followed by `B` or `iB`); a bare number is in MB.
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let size = size.trim();
+    let split = size
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("'{}' is not a size like 500M or 2G", size))?;
+    let unit = unit.trim().to_ascii_uppercase();
+    let exponent = match unit.trim_end_matches("IB").trim_end_matches('B') {
+        "" if unit.is_empty() => 2,
+        "" => 0,
+        "K" => 1,
+        "M" => 2,
+        "G" => 3,
+        "T" => 4,
+        _ => return Err(format!("unknown unit '{}' (expected K, M, G or T)", unit)),
+    };
+
+    // `as` would silently saturate at u64::MAX
+    let bytes = number * 1024f64.powi(exponent);
+    if bytes >= u64::MAX as f64 {
+        return Err(format!("'{}' is too large a size", size));
+    }
+    Ok(bytes as u64)
+}
+
+/// Get the config file path
+pub fn get_config_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME")?;
+    Ok(PathBuf::from(home).join(CONFIG_DIR).join(CONFIG_FILE))
+}
+
+/// Load the config file, falling back to defaults when it doesn't exist
+pub fn load_config() -> Result<Config> {
+    let config_path = get_config_path()?;
+
+    if !config_path.exists() {
+        return Ok(Config::default());
+    }
+
+    let contents = fs::read_to_string(&config_path)
+        .with_context(|| format!("Failed to read config from {:?}", config_path))?;
+
+    toml::from_str(&contents).with_context(|| format!("Invalid config file {:?}", config_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn oversized_durations_and_sizes_are_rejected() {
+        assert_eq!(parse_duration("2h"), Ok(7200));
+        assert!(parse_duration("999999999999999999d").is_err());
+        assert_eq!(parse_size("1.5G"), Ok(1536 * 1024 * 1024));
+        assert!(parse_size("99999999999T").is_err());
+    }
+}
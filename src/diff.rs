@@ -0,0 +1,169 @@
+use crate::output;
+use crate::types::*;
+use colored::Colorize;
+use humansize::{format_size, BINARY};
+use std::collections::{BTreeMap, HashMap};
+
+/// Compare two scans, matching items by path
+pub fn diff_results(old: &ScanResults, new: &ScanResults) -> ScanDiff {
+    let old_items: HashMap<&str, &CleanableItem> = old
+        .items
+        .iter()
+        .map(|item| (item.path.as_str(), item))
+        .collect();
+    let new_items: HashMap<&str, &CleanableItem> = new
+        .items
+        .iter()
+        .map(|item| (item.path.as_str(), item))
+        .collect();
+
+    let mut diff = ScanDiff {
+        old_total: old.total_size,
+        new_total: new.total_size,
+        ..ScanDiff::default()
+    };
+
+    for item in &new.items {
+        match old_items.get(item.path.as_str()) {
+            None => diff.added.push(item.clone()),
+            Some(old_item) if old_item.size != item.size => diff.changed.push(ItemChange {
+                item: item.clone(),
+                old_size: old_item.size,
+            }),
+            Some(_) => {}
+        }
+    }
+
+    diff.removed = old
+        .items
+        .iter()
+        .filter(|item| !new_items.contains_key(item.path.as_str()))
+        .cloned()
+        .collect();
+
+    let mut totals: BTreeMap<CleanCategory, (u64, u64)> = BTreeMap::new();
+    for (category, size) in old.total_by_category() {
+        totals.entry(category).or_default().0 = size;
+    }
+    for (category, size) in new.total_by_category() {
+        totals.entry(category).or_default().1 = size;
+    }
+    diff.categories = totals
+        .into_iter()
+        .filter(|(_, (old_size, new_size))| old_size != new_size)
+        .map(|(category, (old_size, new_size))| CategoryDelta {
+            category,
+            old_size,
+            new_size,
+        })
+        .collect();
+    diff.categories
+        .sort_by_key(|delta| std::cmp::Reverse(delta.delta().unsigned_abs()));
+
+    diff
+}
+
+/// Format a signed byte count as `+1.2 GiB` or `-300 MiB`
+fn format_delta(bytes: i64) -> String {
+    let sign = if bytes < 0 { "-" } else { "+" };
+    format!("{}{}", sign, format_size(bytes.unsigned_abs(), BINARY))
+}
+
+/// Print a full comparison of two scans
+pub fn display_diff(diff: &ScanDiff) {
+    println!("\n{}", "=== Scan Diff ===".green().bold());
+    let total_delta = diff.new_total as i64 - diff.old_total as i64;
+    println!(
+        "Total cleanable space: {} -> {} ({})",
+        format_size(diff.old_total, BINARY),
+        format_size(diff.new_total, BINARY),
+        format_delta(total_delta).bold()
+    );
+
+    if !diff.categories.is_empty() {
+        println!("\n{}", "By category:".bold());
+        for delta in &diff.categories {
+            println!(
+                "  {:<20} {} -> {} ({})",
+                delta.category.to_string(),
+                format_size(delta.old_size, BINARY),
+                format_size(delta.new_size, BINARY),
+                format_delta(delta.delta())
+            );
+        }
+    }
+
+    if !diff.added.is_empty() {
+        println!("\n{} ({} items)", "Added:".green().bold(), diff.added.len());
+        for item in &diff.added {
+            println!("  {} - {}", format_size(item.size, BINARY), item.path);
+        }
+    }
+
+    if !diff.removed.is_empty() {
+        println!(
+            "\n{} ({} items)",
+            "Removed:".red().bold(),
+            diff.removed.len()
+        );
+        for item in &diff.removed {
+            println!("  {} - {}", format_size(item.size, BINARY), item.path);
+        }
+    }
+
+    if !diff.changed.is_empty() {
+        println!(
+            "\n{} ({} items)",
+            "Changed:".yellow().bold(),
+            diff.changed.len()
+        );
+        for change in &diff.changed {
+            println!("  {} - {}", format_delta(change.growth()), change.item.path);
+        }
+    }
+}
+
+/// Print new items, items that grew by more than `min_growth` bytes and items
+/// that disappeared
+pub fn display_growth(diff: &ScanDiff, min_growth: u64) {
+    let grown: Vec<&ItemChange> = diff
+        .changed
+        .iter()
+        .filter(|change| change.growth() > min_growth as i64)
+        .collect();
+
+    if diff.added.is_empty() && grown.is_empty() && diff.removed.is_empty() {
+        output::status("No significant changes".dimmed());
+        return;
+    }
+
+    for item in &diff.added {
+        println!(
+            "{} {} - {} ({})",
+            "+ new".green().bold(),
+            format_size(item.size, BINARY),
+            item.path,
+            item.category
+        );
+    }
+
+    for change in grown {
+        println!(
+            "{} {} -> {} (+{}) - {}",
+            "↑ grew".yellow().bold(),
+            format_size(change.old_size, BINARY),
+            format_size(change.item.size, BINARY),
+            format_size(change.growth() as u64, BINARY),
+            change.item.path
+        );
+    }
+
+    for item in &diff.removed {
+        println!(
+            "{} {} - {}",
+            "- gone".red().bold(),
+            format_size(item.size, BINARY),
+            item.path.dimmed()
+        );
+    }
+}
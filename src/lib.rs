@@ -0,0 +1,21 @@
+//! Scan for and clean reclaimable storage on macOS.
+//!
+//! The `cleanser` binary is a thin CLI over this library. [`scan`] never prints;
+//! pass a progress callback to receive [`ScanEvent`]s and render your own UI.
+
+pub mod cache;
+pub mod cleaner;
+pub mod config;
+pub mod diff;
+pub mod output;
+pub mod paths;
+pub mod review;
+pub mod scanner;
+pub mod schedule;
+pub mod types;
+
+pub use cache::{clear_cache, get_cache_age, load_history, load_scan_results, save_scan_results};
+pub use cleaner::clean;
+pub use diff::diff_results;
+pub use scanner::scan;
+pub use types::*;
@@ -1,17 +1,36 @@
-mod cache;
-mod cleaner;
-mod scanner;
-mod types;
-
+use anyhow::Context;
 use clap::{Parser, Subcommand};
+use cleanser::types::{
+    CleanCategory, ColorChoice, GroupBy, OutputFormat, RiskLevel, ScanEvent, ScanPhase, ScanPreset,
+    ScanSpeed, SortOrder,
+};
+use cleanser::{cache, cleaner, config, diff, output, review, scanner, schedule, types};
 use colored::Colorize;
-use types::{RiskLevel, ScanSpeed};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(name = "cleanser")]
 #[command(about = "A fast CLI tool for clearing macOS storage space", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// Suppress status messages (results are still printed)
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// When to use colors
+    #[arg(long, global = true, default_value = "auto")]
+    color: ColorChoice,
+
+    /// Maximum number of threads for parallel work (default: one per CPU)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u16).range(1..))]
+    threads: Option<u16>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -24,15 +43,50 @@ enum Commands {
         #[arg(short, long, default_value = "normal")]
         speed: ScanSpeed,
 
-        /// Paths to scan (defaults to home directory)
+        /// Paths to scan (defaults to home directory); `-` reads a list of
+        /// paths from stdin, as for --paths-from
         #[arg(short, long)]
         paths: Vec<String>,
 
+        /// Also scan the paths listed in this file (`-` for stdin), one per
+        /// line (blank lines and `#` comments are ignored)
+        #[arg(long, value_name = "FILE")]
+        paths_from: Option<PathBuf>,
+
+        /// Path lists read with `--paths -` or --paths-from are separated by
+        /// NUL characters, as written by `find -print0`, instead of newlines
+        #[arg(long)]
+        null: bool,
+
+        /// Scan for one kind of clutter: dev (build artifacts and
+        /// virtualenvs), media (large media files), system (caches and logs)
+        /// or full. Other flags still apply on top.
+        #[arg(long, default_value = "full")]
+        preset: ScanPreset,
+
         /// Minimum file size in MB for large file detection
         #[arg(long, default_value = "100")]
         min_size: u64,
 
-        /// Maximum depth for directory traversal
+        /// Only report large files with this extension, e.g. dmg or tar.gz (repeatable)
+        #[arg(long, value_name = "EXT")]
+        ext: Vec<String>,
+
+        /// Don't report large files with this extension (repeatable)
+        #[arg(long, value_name = "EXT")]
+        exclude_ext: Vec<String>,
+
+        /// Minimum size in MB for cache, build, Xcode and Docker directories, the
+        /// Trash and old downloads to be reported
+        #[arg(long, default_value = "1")]
+        min_dir_size: u64,
+
+        /// Minimum size in MB for log files to be reported
+        #[arg(long, default_value = "10")]
+        log_min_size: u64,
+
+        /// How many levels below each scan path to search, in every phase
+        /// (sizes still cover the whole of what's found)
         #[arg(long)]
         max_depth: Option<usize>,
 
@@ -40,17 +94,131 @@ enum Commands {
         #[arg(long)]
         find_duplicates: bool,
 
-        /// Output results as JSON
+        /// Minimum file size in KB for duplicate detection (lower values hash many more files)
+        #[arg(long, default_value = "1024")]
+        dup_min_size: u64,
+
+        /// Files to hash at once when finding duplicates (default: one per thread up to 8,
+        /// or 1 on network volumes); use 1 for spinning disks
+        #[arg(long, value_parser = clap::value_parser!(u16).range(1..))]
+        hash_threads: Option<u16>,
+
+        /// Follow symbolic links when scanning (loops are detected and skipped)
+        #[arg(long)]
+        follow_symlinks: bool,
+
+        /// Estimate directory sizes from a capped walk (faster, less accurate)
+        #[arg(long)]
+        estimate: bool,
+
+        /// Report apparent file sizes (like `ls`) instead of disk usage (like `du`)
+        #[arg(long)]
+        apparent_size: bool,
+
+        /// Also show each directory's size including what symlinks inside it
+        /// point to (deleting still only removes the links)
+        #[arg(long)]
+        dereference_size: bool,
+
+        /// Fail if any scan path doesn't exist instead of skipping it
+        #[arg(long)]
+        strict_paths: bool,
+
+        /// Don't scan for cache directories
+        #[arg(long)]
+        skip_caches: bool,
+
+        /// Don't scan for build artifacts (node_modules, target, ...)
+        #[arg(long)]
+        skip_build_artifacts: bool,
+
+        /// Don't report build artifacts inside git repositories with uncommitted changes
+        #[arg(long)]
+        skip_dirty_repos: bool,
+
+        /// Rate build artifacts by their git repository's last commit: Safe
+        /// when none for 180+ days, Risky when committed to in the last week
+        #[arg(long)]
+        repo_activity: bool,
+
+        /// Don't scan for log files
+        #[arg(long)]
+        skip_logs: bool,
+
+        /// Don't scan for large files
+        #[arg(long)]
+        skip_large_files: bool,
+
+        /// Don't look for duplicate files
+        #[arg(long)]
+        skip_duplicates: bool,
+
+        /// Output results as JSON (same as `--format json`)
         #[arg(long)]
         json: bool,
 
+        /// Output format
+        #[arg(long, default_value = "pretty")]
+        format: OutputFormat,
+
+        /// Write results to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Post a notification when the reclaimable total reaches this size (e.g. 5G)
+        #[arg(long, value_parser = config::parse_size)]
+        notify_threshold: Option<u64>,
+
+        /// Exit with status 1 when the reclaimable total is above this size
+        /// (e.g. 5G; anything at all when no size is given)
+        #[arg(long, value_parser = config::parse_size, num_args = 0..=1, default_missing_value = "0B")]
+        fail_if_found: Option<u64>,
+
         /// Don't save scan results to cache
         #[arg(long)]
         no_cache: bool,
+
+        /// Also keep the results as a named snapshot, for `diff` to compare
+        /// against later (replaces any snapshot with the same name)
+        #[arg(long, value_name = "NAME", value_parser = cache::parse_snapshot_name)]
+        save_as: Option<String>,
+
+        /// Reuse the cached scan, only searching top-level directories modified since
+        #[arg(long)]
+        incremental: bool,
+
+        /// Order items by size (largest first) or age (oldest first)
+        #[arg(long, default_value = "size")]
+        sort: SortOrder,
+
+        /// Fold categories smaller than this (e.g. 100M) into a single "Other" line
+        #[arg(long, value_parser = config::parse_size, default_value = "0")]
+        collapse_below: u64,
+
+        /// What the report is grouped by: risk level then category, category
+        /// alone, or the directory two levels below the scanned path
+        #[arg(long, default_value = "risk", conflicts_with_all = ["json", "format", "summary"])]
+        group_by: GroupBy,
+
+        /// Print only the total and the subtotal per risk level, on one line
+        #[arg(long, conflicts_with_all = ["json", "format"])]
+        summary: bool,
+
+        /// Print how long each phase took (to stderr) once the scan finishes
+        #[arg(long)]
+        profile: bool,
+
+        /// List every item along with the rule that flagged it
+        #[arg(long, conflicts_with = "summary")]
+        explain: bool,
+
+        /// Also print a histogram of where the space is, by item size
+        #[arg(long, conflicts_with_all = ["json", "format"])]
+        histogram: bool,
     },
     /// Clean files based on risk level
     Clean {
-        /// Maximum risk level to clean (safe/moderate/risky)
+        /// Maximum risk level to clean (safe/moderate/risky/manual)
         #[arg(short, long, default_value = "safe")]
         risk: RiskLevel,
 
@@ -58,6 +226,15 @@ enum Commands {
         #[arg(short = 'y', long)]
         yes: bool,
 
+        /// Ask about each item: delete, skip, delete its whole category, or quit
+        #[arg(long, conflicts_with = "yes")]
+        confirm_each: bool,
+
+        /// Skip the confirmation prompt when less than this much (e.g. 500M)
+        /// would be freed, and ask as usual above it
+        #[arg(long, value_parser = config::parse_size, conflicts_with_all = ["yes", "confirm_each"])]
+        confirm_threshold: Option<u64>,
+
         /// Dry run - show what would be deleted without deleting
         #[arg(long)]
         dry_run: bool,
@@ -65,23 +242,335 @@ enum Commands {
         /// Force a fresh scan instead of using cached results
         #[arg(long)]
         force_scan: bool,
+
+        /// Clean the items in this `scan --json` output (edited to drop what
+        /// you want to keep, say) instead of the cached scan
+        #[arg(long, value_name = "FILE", conflicts_with = "force_scan")]
+        load: Option<PathBuf>,
+
+        /// Retry permission-denied deletions with sudo
+        #[arg(long)]
+        sudo: bool,
+
+        /// Output the cleanup report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Only clean this category, using the names from `scan --json` or
+        /// `custom:<label>` (repeatable)
+        #[arg(long, value_parser = parse_category)]
+        category: Vec<CleanCategory>,
+
+        /// Only clean scanned items at or under this path (repeatable); fails
+        /// if the scan has nothing there
+        #[arg(long = "path", value_name = "PATH")]
+        paths: Vec<PathBuf>,
+
+        /// Only clean items whose path, or a folder containing it, matches
+        /// this glob, e.g. '~/Library/Caches/Google*' (repeatable)
+        #[arg(long = "match", value_name = "GLOB")]
+        patterns: Vec<String>,
+
+        /// Allow deleting critical locations (/, your home folder, system
+        /// directories, ~/.ssh, ...) which are otherwise always skipped
+        #[arg(long = "i-know-what-im-doing")]
+        allow_critical: bool,
+
+        /// Reuse a cached scan up to this old, e.g. 10m, 2h or 1d (default 1h,
+        /// or `cache_max_age` from the config file)
+        #[arg(long, value_parser = config::parse_duration)]
+        cache_max_age: Option<u64>,
+
+        /// When running on battery, clean only Safe items whatever --risk says,
+        /// so nothing that's expensive to rebuild or re-download goes
+        #[arg(long)]
+        on_battery_safe_only: bool,
+
+        /// Remove outdated Homebrew versions by running `brew cleanup` on their
+        /// formulae (without this they're skipped)
+        #[arg(long)]
+        apply_brew_cleanup: bool,
+    },
+    /// Summarize reclaimable space from the latest scan
+    Stats,
+    /// Pick which copy of each set of duplicates from the latest scan to keep
+    Dedupe {
+        /// Dry run - show what would be deleted without deleting
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Retry permission-denied deletions with sudo
+        #[arg(long)]
+        sudo: bool,
+    },
+    /// Browse the latest scan interactively and pick items to delete
+    Review {
+        /// Retry permission-denied deletions with sudo
+        #[arg(long)]
+        sudo: bool,
+    },
+    /// Re-scan periodically and report items that appear, grow or disappear
+    Watch {
+        /// Speed of each scan (quick/normal/thorough)
+        #[arg(short, long, default_value = "quick")]
+        speed: ScanSpeed,
+
+        /// Paths to watch (defaults to home directory)
+        #[arg(short, long)]
+        paths: Vec<String>,
+
+        /// Seconds between scans
+        #[arg(long, default_value = "300")]
+        interval: u64,
+
+        /// Report items that grew by more than this many MB between scans
+        #[arg(long, default_value = "100")]
+        min_growth: u64,
+    },
+    /// Compare two scans saved with `scan --json > file` or `scan --save-as`
+    Diff {
+        /// The older scan results: a file or a snapshot name
+        old: String,
+
+        /// The newer scan results: a file or a snapshot name
+        new: String,
+
+        /// Output the differences as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// List the snapshots saved with `scan --save-as`
+    Snapshots,
+    /// Run a scan (or a safe clean) every day with launchd
+    Schedule {
+        /// Time of day to run, as HH:MM
+        #[arg(long, default_value = "03:00", value_parser = schedule::parse_time)]
+        at: (u8, u8),
+
+        /// Clean Safe items instead of only scanning
+        #[arg(long)]
+        clean: bool,
+
+        /// Notify when a scheduled scan finds at least this much to reclaim (e.g. 5G)
+        #[arg(long, value_parser = config::parse_size, conflicts_with = "clean")]
+        notify_threshold: Option<u64>,
+
+        /// Remove the scheduled job
+        #[arg(long, conflicts_with_all = ["at", "clean", "notify_threshold"])]
+        uninstall: bool,
+    },
+    /// Print the JSON Schema of the `--json` output
+    Schema {
+        /// Print the schema of `clean --json` instead of `scan --json`
+        #[arg(long)]
+        clean: bool,
     },
 }
 
+/// Open `--output` for writing, creating its directory if needed. Colors are
+/// turned off since they'd end up in the file as escape codes.
+fn create_output_file(path: &Path) -> anyhow::Result<BufWriter<File>> {
+    let path = PathBuf::from(cleanser::paths::expand_path(&path.to_string_lossy()));
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    let file = File::create(&path).with_context(|| format!("Failed to create {:?}", path))?;
+
+    colored::control::set_override(false);
+    Ok(BufWriter::new(file))
+}
+
+/// Read scan results from a snapshot with this name, or else from a file
+/// saved with `scan --json`
+fn read_scan_arg(arg: &str) -> anyhow::Result<types::ScanResults> {
+    let path = Path::new(arg);
+    if !path.exists() {
+        return match cache::load_snapshot(arg) {
+            Ok(Some(snapshot)) => Ok(snapshot.results),
+            _ => anyhow::bail!(
+                "No file or snapshot named '{}' (`cleanser snapshots` lists snapshots)",
+                arg
+            ),
+        };
+    }
+    cache::load_scan_file(path)
+}
+
+/// Read the paths listed in a `--paths-from` file, or on stdin for `-`. An
+/// empty list is an error rather than a silent fall back to scanning the
+/// home folder.
+fn read_path_list(file: &Path, null_separated: bool) -> anyhow::Result<Vec<String>> {
+    let (contents, source) = if file == Path::new("-") {
+        let mut contents = String::new();
+        io::stdin()
+            .read_to_string(&mut contents)
+            .context("Failed to read paths from stdin")?;
+        (contents, "stdin".to_string())
+    } else {
+        let file = PathBuf::from(cleanser::paths::expand_path(&file.to_string_lossy()));
+        let contents = std::fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read paths from {}", file.display()))?;
+        (contents, file.display().to_string())
+    };
+
+    let paths = cleanser::paths::parse_path_list(&contents, null_separated);
+    if paths.is_empty() {
+        anyhow::bail!("No paths listed in {}", source);
+    }
+    Ok(paths)
+}
+
+/// Make a `clean --path` argument comparable with scanned item paths:
+/// expanded, absolute, and with its parent directories resolved the way the
+/// scanner resolves item paths
+fn resolve_clean_path(path: &Path) -> anyhow::Result<PathBuf> {
+    let expanded = cleanser::paths::expand_path(&path.to_string_lossy());
+    let absolute = std::path::absolute(&expanded)
+        .with_context(|| format!("Invalid path {}", path.display()))?;
+    Ok(cleanser::paths::canonicalize_parent(
+        &absolute.components().collect::<PathBuf>(),
+    ))
+}
+
+/// Sleep for `duration`, returning false early if `stop` gets set
+fn wait_unless_stopped(stop: &AtomicBool, duration: Duration) -> bool {
+    let deadline = Instant::now() + duration;
+    while Instant::now() < deadline {
+        if stop.load(Ordering::SeqCst) {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(200));
+    }
+    !stop.load(Ordering::SeqCst)
+}
+
+fn parse_category(name: &str) -> Result<CleanCategory, String> {
+    CleanCategory::from_machine_name(name).ok_or_else(|| {
+        let valid: Vec<_> = CleanCategory::ALL
+            .iter()
+            .map(|c| c.machine_name())
+            .collect();
+        format!(
+            "unknown category '{}' (expected one of: {}, or custom:<label>)",
+            name,
+            valid.join(", ")
+        )
+    })
+}
+
 fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    output::set_quiet(cli.quiet);
+
+    match cli.color {
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+        ColorChoice::Auto => {
+            let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+            if no_color || !io::stdout().is_terminal() {
+                colored::control::set_override(false);
+            }
+        }
+    }
+
+    if let Some(threads) = cli.threads {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(threads.into())
+            .build_global()
+            .context("Failed to configure the thread pool")?;
+    }
 
     match cli.command {
         Commands::Scan {
             speed,
             paths,
+            paths_from,
+            null,
+            preset,
             min_size,
+            ext,
+            exclude_ext,
+            min_dir_size,
+            log_min_size,
             max_depth,
             find_duplicates,
+            dup_min_size,
+            hash_threads,
+            follow_symlinks,
+            estimate,
+            apparent_size,
+            dereference_size,
+            strict_paths,
+            skip_caches,
+            skip_build_artifacts,
+            skip_dirty_repos,
+            repo_activity,
+            skip_logs,
+            skip_large_files,
+            skip_duplicates,
             json,
+            format,
+            output,
+            notify_threshold,
+            fail_if_found,
             no_cache,
+            save_as,
+            incremental,
+            sort,
+            collapse_below,
+            group_by,
+            summary,
+            profile,
+            explain,
+            histogram,
         } => {
-            println!("{}", format!("Scanning with {} speed...", speed).cyan());
+            let from_stdin = paths.iter().any(|path| path == "-");
+            let mut paths: Vec<String> = paths.into_iter().filter(|path| path != "-").collect();
+            if from_stdin {
+                paths.extend(read_path_list(Path::new("-"), null)?);
+            }
+            if let Some(file) = &paths_from {
+                paths.extend(read_path_list(file, null)?);
+            }
+
+            output::status(format!("Scanning with {} speed...", speed).cyan());
+
+            if find_duplicates && dup_min_size < 1024 {
+                output::status(
+                    format!(
+                        "Warning: hashing files down to {} KB for duplicates can make the scan much slower",
+                        dup_min_size
+                    )
+                    .yellow(),
+                );
+            }
+
+            let mut enabled_phases = preset.phases();
+            for (skip, phase) in [
+                (skip_caches, ScanPhase::Caches),
+                (skip_build_artifacts, ScanPhase::BuildArtifacts),
+                (skip_logs, ScanPhase::Logs),
+                (skip_large_files, ScanPhase::LargeFiles),
+                (skip_duplicates, ScanPhase::Duplicates),
+            ] {
+                if skip {
+                    enabled_phases.remove(&phase);
+                }
+            }
+
+            let previous = match incremental.then(cache::load_cached_scan).transpose()? {
+                Some(Some(cached)) => Some(types::PreviousScan {
+                    timestamp: cached.timestamp,
+                    scanned_paths: cached.results.scanned_paths,
+                    items: cached.results.items,
+                }),
+                Some(None) => {
+                    output::status("No cached scan to build on, running a full scan".yellow());
+                    None
+                }
+                None => None,
+            };
 
             let config = types::ScanConfig {
                 speed,
@@ -89,56 +578,348 @@ fn main() -> anyhow::Result<()> {
                     vec![std::env::var("HOME")?]
                 } else {
                     paths
+                        .iter()
+                        .map(|p| cleanser::paths::expand_path(p))
+                        .collect()
                 },
                 min_file_size_mb: min_size,
-                max_depth,
+                extensions: if ext.is_empty() {
+                    preset.extensions()
+                } else {
+                    ext
+                },
+                excluded_extensions: exclude_ext,
+                min_dir_size_mb: min_dir_size,
+                log_min_size_mb: log_min_size,
+                max_depth: max_depth.or(preset.max_depth()),
                 find_duplicates,
+                dup_min_size_kb: dup_min_size,
+                // --threads caps an explicit --hash-threads too
+                hash_threads: hash_threads
+                    .map(|n| cli.threads.map_or(n, |limit| n.min(limit)).into()),
+                follow_symlinks,
+                estimate,
+                apparent_size,
+                dereference_size,
+                strict_paths,
+                enabled_phases,
+                skip_dirty_repos,
+                repo_activity,
+                custom_rules: config::load_config()?.rules,
+                previous,
             };
 
-            let results = scanner::scan(config)?;
+            let spinner = output::Spinner::new();
+            let profiles = RefCell::new(Vec::new());
+            let started = Instant::now();
+            let results = scanner::scan(
+                config,
+                Some(&|event| {
+                    if let ScanEvent::PhaseFinished(phase) = &event {
+                        profiles.borrow_mut().push(phase.clone());
+                    }
+                    spinner.handle(event)
+                }),
+            )?;
+            let elapsed = started.elapsed();
 
             // Save to cache unless --no-cache is specified
             if !no_cache {
                 if let Err(e) = cache::save_scan_results(&results) {
-                    eprintln!(
-                        "{}",
-                        format!("Warning: Failed to save scan cache: {}", e).yellow()
-                    );
+                    output::warn(format!("Failed to save scan cache: {}", e));
                 }
             }
 
-            if json {
-                println!("{}", serde_json::to_string_pretty(&results)?);
-            } else {
-                scanner::display_results(&results);
+            if let Some(name) = &save_as {
+                let snapshot_path = cache::save_snapshot(name, &results)?;
+                output::status(
+                    format!("Saved snapshot '{}' to {}", name, snapshot_path.display()).cyan(),
+                );
+            }
+
+            let format = if json { OutputFormat::Json } else { format };
+            let mut out: Box<dyn Write> = match &output {
+                Some(path) => Box::new(create_output_file(path)?),
+                None => Box::new(io::stdout().lock()),
+            };
+
+            match format {
+                OutputFormat::Pretty if summary => scanner::display_summary(&mut out, &results)?,
+                OutputFormat::Pretty => {
+                    // Paths written to a file are never cut short
+                    let width = match output {
+                        Some(_) => usize::MAX,
+                        None => output::terminal_width().unwrap_or(output::FALLBACK_WIDTH),
+                    };
+                    scanner::display_results(
+                        &mut out,
+                        &results,
+                        sort,
+                        collapse_below,
+                        explain,
+                        width,
+                        group_by,
+                    )?
+                }
+                OutputFormat::Json => writeln!(out, "{}", serde_json::to_string_pretty(&results)?)?,
+                OutputFormat::Csv => scanner::write_csv(&mut out, &results, sort)?,
+                OutputFormat::Du => scanner::write_du(&mut out, &results, sort)?,
+                OutputFormat::Table => {
+                    // Only fit rows to the terminal when they're going to it
+                    let width = output.is_none().then(output::terminal_width).flatten();
+                    scanner::write_table(&mut out, &results, sort, width)?
+                }
+            }
+            if histogram {
+                scanner::display_histogram(&mut out, &results)?;
+            }
+            out.flush()?;
+
+            if let Some(path) = output {
+                output::status(format!("Results written to {}", path.display()).green());
+            }
+
+            if profile {
+                scanner::display_profile(&profiles.into_inner(), elapsed);
+            }
+
+            if notify_threshold.is_some_and(|threshold| results.total_size >= threshold) {
+                output::notify(
+                    "Cleanser",
+                    &format!(
+                        "{} can be reclaimed ({} items). Run `cleanser clean` to free it.",
+                        humansize::format_size(results.total_size, humansize::BINARY),
+                        results.items.len()
+                    ),
+                );
+            }
+
+            if fail_if_found.is_some_and(|threshold| {
+                !results.items.is_empty() && results.total_size > threshold
+            }) {
+                std::process::exit(1);
             }
         }
         Commands::Clean {
             risk,
             yes,
+            confirm_each,
+            confirm_threshold,
             dry_run,
             force_scan,
+            load,
+            sudo,
+            json,
+            category,
+            paths,
+            patterns,
+            allow_critical,
+            cache_max_age,
+            on_battery_safe_only,
+            apply_brew_cleanup,
         } => {
             if dry_run {
-                println!("{}", "DRY RUN MODE - No files will be deleted".yellow());
+                output::status("DRY RUN MODE - No files will be deleted".yellow());
+            }
+
+            output::status(format!("Cleaning with maximum risk level: {}", risk).cyan());
+
+            let report = cleaner::clean(types::CleanConfig {
+                max_risk: risk,
+                dry_run,
+                force_scan,
+                load: load.map(|path| {
+                    PathBuf::from(cleanser::paths::expand_path(&path.to_string_lossy()))
+                }),
+                yes,
+                confirm_each,
+                confirm_threshold,
+                sudo,
+                json,
+                categories: category,
+                paths: paths
+                    .iter()
+                    .map(|path| resolve_clean_path(path))
+                    .collect::<anyhow::Result<_>>()?,
+                patterns,
+                allow_critical,
+                cache_max_age: match cache_max_age {
+                    Some(age) => Some(age),
+                    None => config::load_config()?.cache_max_age_secs()?,
+                },
+                on_battery_safe_only,
+                apply_brew_cleanup,
+            })?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+
+            if report.failed > 0 {
+                anyhow::bail!("{} items failed to clean", report.failed);
+            }
+        }
+        Commands::Stats => {
+            let results = match cache::load_scan_results(Some(u64::MAX)) {
+                Ok(Some(results)) => results,
+                _ => {
+                    output::status("No cached scan found, running fresh scan...".cyan());
+                    cleaner::run_fresh_scan()?
+                }
+            };
+
+            let history = cache::load_history().unwrap_or_default();
+            scanner::display_stats(&results, &history);
+        }
+        Commands::Dedupe { dry_run, sudo } => {
+            let groups = match cache::load_scan_results(Some(u64::MAX))? {
+                Some(results) => results.duplicate_groups,
+                None => Vec::new(),
+            };
+
+            let report = cleaner::resolve_duplicates(&groups, dry_run, sudo)?;
+            if report.failed > 0 {
+                anyhow::bail!("{} files failed to delete", report.failed);
             }
+        }
+        Commands::Review { sudo } => {
+            let Some(results) = cache::load_scan_results(Some(u64::MAX))? else {
+                anyhow::bail!("No saved scan to review (run `cleanser scan` first)");
+            };
+
+            let marked = review::run(&results)?;
+            if marked.is_empty() {
+                output::status("Nothing deleted.");
+                return Ok(());
+            }
+
+            let report = cleaner::clean_selected(&marked, sudo);
+            if report.failed > 0 {
+                anyhow::bail!("{} items failed to clean", report.failed);
+            }
+        }
+        Commands::Watch {
+            speed,
+            paths,
+            interval,
+            min_growth,
+        } => {
+            // First Ctrl-C stops after the current scan, a second exits at once
+            let stop = Arc::new(AtomicBool::new(false));
+            let handler_stop = Arc::clone(&stop);
+            ctrlc::set_handler(move || {
+                if handler_stop.swap(true, Ordering::SeqCst) {
+                    std::process::exit(130);
+                }
+            })?;
 
-            println!(
-                "{}",
-                format!("Cleaning with maximum risk level: {}", risk).cyan()
+            let config = types::ScanConfig {
+                speed,
+                paths: if paths.is_empty() {
+                    vec![std::env::var("HOME")?]
+                } else {
+                    paths
+                        .iter()
+                        .map(|p| cleanser::paths::expand_path(p))
+                        .collect()
+                },
+                min_file_size_mb: 0,
+                extensions: Vec::new(),
+                excluded_extensions: Vec::new(),
+                min_dir_size_mb: 1,
+                log_min_size_mb: 10,
+                max_depth: None,
+                find_duplicates: false,
+                dup_min_size_kb: 1024,
+                hash_threads: None,
+                follow_symlinks: false,
+                estimate: false,
+                apparent_size: false,
+                dereference_size: false,
+                strict_paths: false,
+                enabled_phases: ScanPhase::all(),
+                skip_dirty_repos: false,
+                repo_activity: false,
+                custom_rules: config::load_config()?.rules,
+                previous: None,
+            };
+
+            let spinner = output::Spinner::new();
+            let mut previous = scanner::scan(config.clone(), Some(&|event| spinner.handle(event)))?;
+            output::status(
+                format!(
+                    "Watching {} items ({}) every {}s, press Ctrl-C to stop",
+                    previous.items.len(),
+                    humansize::format_size(previous.total_size, humansize::BINARY),
+                    interval
+                )
+                .cyan(),
             );
 
-            if !yes && !dry_run {
-                println!("{}", "This will delete files. Continue? (y/N)".yellow());
-                let mut input = String::new();
-                std::io::stdin().read_line(&mut input)?;
-                if !input.trim().eq_ignore_ascii_case("y") {
-                    println!("Cancelled.");
-                    return Ok(());
+            while wait_unless_stopped(&stop, Duration::from_secs(interval)) {
+                let spinner = output::Spinner::new();
+                let current = scanner::scan(config.clone(), Some(&|event| spinner.handle(event)))?;
+                if stop.load(Ordering::SeqCst) {
+                    break;
                 }
+
+                let diff = cleanser::diff_results(&previous, &current);
+                diff::display_growth(&diff, min_growth * 1024 * 1024);
+                previous = current;
             }
 
-            cleaner::clean(risk, dry_run, force_scan)?;
+            output::status("Stopped watching".cyan());
+        }
+        Commands::Diff { old, new, json } => {
+            let diff = cleanser::diff_results(&read_scan_arg(&old)?, &read_scan_arg(&new)?);
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&diff)?);
+            } else {
+                diff::display_diff(&diff);
+            }
+        }
+        Commands::Snapshots => {
+            scanner::display_snapshots(&cache::list_snapshots()?);
+        }
+        Commands::Schedule {
+            at: (hour, minute),
+            clean,
+            notify_threshold,
+            uninstall,
+        } => {
+            if uninstall {
+                if schedule::uninstall()? {
+                    output::status("Removed the scheduled job".green());
+                } else {
+                    output::status("No scheduled job is installed".yellow());
+                }
+            } else {
+                let task = if clean {
+                    schedule::ScheduledTask::Clean
+                } else {
+                    schedule::ScheduledTask::Scan { notify_threshold }
+                };
+                let plist_path = schedule::install(task, hour, minute)?;
+                output::status(
+                    format!(
+                        "Scheduled a daily {} at {:02}:{:02} ({})",
+                        if clean { "safe clean" } else { "scan" },
+                        hour,
+                        minute,
+                        plist_path.display()
+                    )
+                    .green(),
+                );
+            }
+        }
+        Commands::Schema { clean } => {
+            let schema = if clean {
+                schemars::schema_for!(types::CleanReport)
+            } else {
+                schemars::schema_for!(types::ScanResults)
+            };
+            println!("{}", serde_json::to_string_pretty(&schema)?);
         }
     }
 
@@ -0,0 +1,136 @@
+use crate::types::ScanEvent;
+use colored::Colorize;
+use indicatif::{ProgressBar, ProgressStyle};
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::process::Command;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Silent unless the caller opts in, so the library never prints on its own
+static QUIET: AtomicBool = AtomicBool::new(true);
+
+/// Suppress status messages for the rest of the process
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+/// Whether status messages are suppressed
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Print a human-facing status message to stderr so stdout only carries results
+pub fn status(message: impl Display) {
+    if !is_quiet() {
+        eprintln!("{}", message);
+    }
+}
+
+/// Print a warning to stderr, unless quiet like any status message
+pub fn warn(message: impl Display) {
+    status(format!("Warning: {}", message).yellow());
+}
+
+/// Print a safety message (a refused deletion, say) to stderr even when
+/// quiet, so it still reaches the log of a scheduled `--quiet` run
+pub fn alert(message: impl Display) {
+    eprintln!("{}", message.to_string().red().bold());
+}
+
+/// Columns assumed for human-readable output when stdout isn't a terminal
+pub const FALLBACK_WIDTH: usize = 80;
+
+/// Width of the terminal stdout is attached to, in columns, or None when
+/// stdout isn't a terminal or its size can't be read
+pub fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    ratatui::crossterm::terminal::size()
+        .ok()
+        .map(|(columns, _)| columns as usize)
+        .filter(|&columns| columns > 0)
+}
+
+/// Terminal spinner that renders scan progress, hidden when quiet
+pub struct Spinner {
+    bar: ProgressBar,
+}
+
+impl Spinner {
+    pub fn new() -> Self {
+        let bar = if is_quiet() {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new_spinner()
+        };
+        bar.set_style(spinner_style());
+        Spinner { bar }
+    }
+
+    /// Update the spinner from a scan event
+    pub fn handle(&self, event: ScanEvent) {
+        match event {
+            ScanEvent::PhaseStarted(phase) => {
+                if self.bar.length().is_some() {
+                    self.bar.set_style(spinner_style());
+                    self.bar.unset_length();
+                }
+                self.bar.set_message(phase)
+            }
+            // Hashing turns the spinner into a byte-based bar, so the rate
+            // and ETA reflect how much is left to read
+            ScanEvent::Hashing {
+                files_done,
+                files_total,
+                bytes_done,
+                bytes_total,
+            } => {
+                if self.bar.length().is_none() {
+                    self.bar.reset();
+                    self.bar.set_style(
+                        ProgressStyle::default_bar()
+                            .template(
+                                "{spinner:.green} {msg} [{bar:30}] {binary_bytes}/{binary_total_bytes} ({binary_bytes_per_sec}, {eta} left)",
+                            )
+                            .unwrap()
+                            .progress_chars("=> "),
+                    );
+                    self.bar.set_length(bytes_total);
+                }
+                self.bar.set_position(bytes_done);
+                self.bar
+                    .set_message(format!("Hashing {}/{} files", files_done, files_total));
+            }
+            ScanEvent::Warning(message) => self.bar.suspend(|| warn(message)),
+            ScanEvent::ItemFound(_) | ScanEvent::PhaseFinished(_) => self.bar.tick(),
+            ScanEvent::ScanComplete => self
+                .bar
+                .finish_with_message("Scan complete!".green().to_string()),
+        }
+    }
+}
+
+fn spinner_style() -> ProgressStyle {
+    ProgressStyle::default_spinner()
+        .template("{spinner:.green} {msg}")
+        .unwrap()
+}
+
+impl Default for Spinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Post a macOS notification through Notification Center. Failures are
+/// ignored: a missing notification shouldn't fail the command.
+pub fn notify(title: &str, message: &str) {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let script = format!(
+        "display notification {} with title {}",
+        quote(message),
+        quote(title)
+    );
+    let _ = Command::new("osascript").arg("-e").arg(script).output();
+}
@@ -0,0 +1,200 @@
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Expand user-supplied path syntax the shell would normally handle.
+///
+/// Supported forms:
+/// - a leading `~` or `~/...` (current user's home, from `$HOME`)
+/// - a leading `~user` or `~user/...` (that user's home directory)
+/// - `$VAR` and `${VAR}` anywhere in the path
+///
+/// Anything that can't be resolved (an unknown user, an unset variable) is
+/// left as written rather than silently expanding to an empty string.
+pub fn expand_path(path: &str) -> String {
+    expand_vars(&expand_tilde(path))
+}
+
+fn expand_tilde(path: &str) -> String {
+    let Some(rest) = path.strip_prefix('~') else {
+        return path.to_string();
+    };
+
+    let (user, tail) = match rest.find('/') {
+        Some(i) => (&rest[..i], &rest[i + 1..]),
+        None => (rest, ""),
+    };
+
+    let home = if user.is_empty() {
+        std::env::var("HOME").ok()
+    } else {
+        home_dir_of(user)
+    };
+
+    match home {
+        Some(home) if tail.is_empty() => home,
+        Some(home) => PathBuf::from(home).join(tail).display().to_string(),
+        None => path.to_string(),
+    }
+}
+
+/// Look up another user's home directory in the password database
+fn home_dir_of(user: &str) -> Option<String> {
+    let name = CString::new(user).ok()?;
+
+    // SAFETY: getpwnam returns null or a pointer to a static passwd record,
+    // which we copy out of before making any other libc call
+    unsafe {
+        let entry = libc::getpwnam(name.as_ptr());
+        if entry.is_null() || (*entry).pw_dir.is_null() {
+            return None;
+        }
+        Some(
+            CStr::from_ptr((*entry).pw_dir)
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
+fn expand_vars(path: &str) -> String {
+    let mut expanded = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(i) = rest.find('$') {
+        expanded.push_str(&rest[..i]);
+        let after = &rest[i + 1..];
+
+        let (name, consumed) = if let Some(braced) = after.strip_prefix('{') {
+            match braced.find('}') {
+                Some(end) => (&braced[..end], end + 2),
+                None => ("", 0),
+            }
+        } else {
+            let end = after
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(after.len());
+            (&after[..end], end)
+        };
+
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            _ => expanded.push_str(&rest[i..i + 1 + consumed]),
+        }
+        rest = &after[consumed..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+/// Parse a list of paths, as read by `--paths-from` or from stdin. One path
+/// per line, trimmed, skipping blank lines and `#` comments; or with
+/// `null_separated`, NUL-terminated paths taken exactly as written (as from
+/// `find -print0`), which can contain any other character. Expansion is left
+/// to the caller.
+pub fn parse_path_list(contents: &str, null_separated: bool) -> Vec<String> {
+    if null_separated {
+        return contents
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect()
+}
+
+/// Resolve symlinks and `..` in a path's parent directories so aliases of the
+/// same location compare equal. The final component is kept as is, so a path
+/// that is itself a symlink still names the link rather than its target.
+/// Falls back to the path unchanged when the parent can't be resolved.
+pub fn canonicalize_parent(path: &Path) -> PathBuf {
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_os_str().is_empty() => {
+            match fs::canonicalize(parent) {
+                Ok(parent) => parent.join(name),
+                Err(_) => path.to_path_buf(),
+            }
+        }
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Normalize a user-supplied path for comparison: expand it, resolve symlinks
+/// and `..` when it exists, and drop trailing slashes and `.` components
+pub fn normalize_path(path: &str) -> PathBuf {
+    let expanded = PathBuf::from(expand_path(path));
+    fs::canonicalize(&expanded).unwrap_or_else(|_| expanded.components().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn canonicalize_parent_resolves_a_symlinked_ancestor() {
+        let dir = std::env::temp_dir().join(format!("cleanser-test-{}-parent", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let real = dir.join("real");
+        fs::create_dir_all(real.join("sub")).unwrap();
+        std::os::unix::fs::symlink(&real, dir.join("alias")).unwrap();
+
+        let resolved = canonicalize_parent(&dir.join("alias/sub/item"));
+
+        assert_eq!(resolved, fs::canonicalize(&real).unwrap().join("sub/item"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn expand_path_handles_each_form() {
+        let home = std::env::var("HOME").unwrap();
+        // Only variables no other test touches are set here
+        std::env::set_var("CLEANSER_TEST_EXPAND", "/opt/data");
+        std::env::remove_var("CLEANSER_TEST_UNSET");
+
+        assert_eq!(expand_path("~"), home);
+        assert_eq!(
+            expand_path("~/x"),
+            Path::new(&home).join("x").display().to_string()
+        );
+        assert_eq!(expand_path("$CLEANSER_TEST_EXPAND/x"), "/opt/data/x");
+        assert_eq!(expand_path("${CLEANSER_TEST_EXPAND}x"), "/opt/datax");
+        assert_eq!(
+            expand_path("$CLEANSER_TEST_UNSET/x"),
+            "$CLEANSER_TEST_UNSET/x"
+        );
+        assert_eq!(expand_path("relative/dir"), "relative/dir");
+    }
+
+    #[test]
+    fn expand_path_resolves_named_users() {
+        // SAFETY: the record is copied out before any other libc call
+        let (user, home) = unsafe {
+            let entry = libc::getpwuid(libc::getuid());
+            assert!(!entry.is_null());
+            (
+                CStr::from_ptr((*entry).pw_name)
+                    .to_string_lossy()
+                    .into_owned(),
+                CStr::from_ptr((*entry).pw_dir)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        };
+
+        assert_eq!(
+            expand_path(&format!("~{}/x", user)),
+            Path::new(&home).join("x").display().to_string()
+        );
+        assert_eq!(
+            expand_path("~cleanser-no-such-user/x"),
+            "~cleanser-no-such-user/x"
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::scanner;
+use crate::types::*;
+use anyhow::{bail, Result};
+use humansize::{format_size, BINARY};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Flex, Layout};
+use ratatui::style::{Color, Modifier, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::BTreeSet;
+use std::io::{self, IsTerminal};
+
+/// A line in the review list: a category heading or one of its items
+enum Row<'a> {
+    Category {
+        category: &'a CleanCategory,
+        count: usize,
+        size: u64,
+    },
+    Item(&'a CleanableItem),
+}
+
+struct Review<'a> {
+    rows: Vec<Row<'a>>,
+    /// Indices into `rows` of the items marked for deletion
+    marked: BTreeSet<usize>,
+    state: ListState,
+    total: u64,
+    confirming: bool,
+}
+
+/// Browse scan results grouped by category and mark items for deletion.
+/// Returns the marked items once the user confirms, or nothing if they quit.
+pub fn run(results: &ScanResults) -> Result<Vec<&CleanableItem>> {
+    if !io::stdout().is_terminal() {
+        bail!("Reviewing results needs an interactive terminal");
+    }
+
+    let mut review = Review::new(results);
+    let mut terminal = ratatui::init();
+    let outcome = review.event_loop(&mut terminal);
+    ratatui::restore();
+
+    let confirmed = outcome?;
+    Ok(if confirmed {
+        review.marked_items()
+    } else {
+        Vec::new()
+    })
+}
+
+impl<'a> Review<'a> {
+    fn new(results: &'a ScanResults) -> Self {
+        // Largest categories first, largest items first within each
+        let mut groups: Vec<(&CleanCategory, Vec<&CleanableItem>)> =
+            results.group_by_category().into_iter().collect();
+        let group_size = |items: &[&CleanableItem]| items.iter().map(|i| i.size).sum::<u64>();
+        groups.sort_by(|(a, a_items), (b, b_items)| {
+            group_size(b_items)
+                .cmp(&group_size(a_items))
+                .then_with(|| a.cmp(b))
+        });
+
+        let mut rows = Vec::new();
+        for (category, mut items) in groups {
+            items.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+            rows.push(Row::Category {
+                category,
+                count: items.len(),
+                size: group_size(&items),
+            });
+            rows.extend(items.into_iter().map(Row::Item));
+        }
+
+        let mut state = ListState::default();
+        if !rows.is_empty() {
+            state.select(Some(0));
+        }
+
+        Review {
+            rows,
+            marked: BTreeSet::new(),
+            state,
+            total: results.total_size,
+            confirming: false,
+        }
+    }
+
+    /// Handle keys until the user confirms the deletion (true) or quits (false)
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> Result<bool> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            if self.confirming {
+                match key.code {
+                    KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(true),
+                    _ => self.confirming = false,
+                }
+                continue;
+            }
+
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(false),
+                KeyCode::Down | KeyCode::Char('j') => self.state.select_next(),
+                KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+                KeyCode::PageDown => self.state.scroll_down_by(10),
+                KeyCode::PageUp => self.state.scroll_up_by(10),
+                KeyCode::Home | KeyCode::Char('g') => self.state.select_first(),
+                KeyCode::End | KeyCode::Char('G') => self.state.select_last(),
+                KeyCode::Char(' ') => self.toggle_selected(),
+                KeyCode::Enter | KeyCode::Char('d') if !self.marked.is_empty() => {
+                    self.confirming = true
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Mark or unmark the selected item. On a category heading, mark every
+    /// item in the category, or unmark them all if they're already marked.
+    fn toggle_selected(&mut self) {
+        let Some(selected) = self.state.selected() else {
+            return;
+        };
+
+        match self.rows.get(selected) {
+            Some(Row::Item(_)) if !self.marked.remove(&selected) => {
+                self.marked.insert(selected);
+            }
+            Some(Row::Category { count, .. }) => {
+                let items = selected + 1..=selected + count;
+                if items.clone().all(|i| self.marked.contains(&i)) {
+                    for i in items {
+                        self.marked.remove(&i);
+                    }
+                } else {
+                    self.marked.extend(items);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn marked_items(&self) -> Vec<&'a CleanableItem> {
+        self.marked
+            .iter()
+            .filter_map(|&i| match self.rows[i] {
+                Row::Item(item) => Some(item),
+                Row::Category { .. } => None,
+            })
+            .collect()
+    }
+
+    fn marked_size(&self) -> u64 {
+        self.marked_items().iter().map(|item| item.size).sum()
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [header, list, footer] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let summary = Line::from(vec![
+            Span::raw("Marked: "),
+            Span::raw(format!("{} items", self.marked.len())).bold(),
+            Span::raw(", "),
+            Span::raw(format_size(self.marked_size(), BINARY))
+                .green()
+                .bold(),
+            Span::raw(format!(
+                " of {} reclaimable",
+                format_size(self.total, BINARY)
+            )),
+        ]);
+        frame.render_widget(
+            Paragraph::new(summary).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" cleanser review "),
+            ),
+            header,
+        );
+
+        let items: Vec<ListItem> = self
+            .rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| ListItem::new(self.row_line(i, row)))
+            .collect();
+        let list_widget = List::new(items)
+            .block(Block::default().borders(Borders::LEFT | Borders::RIGHT))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list_widget, list, &mut self.state);
+
+        frame.render_widget(
+            Paragraph::new("↑/↓ move  space mark  enter delete marked  q quit".dark_gray()),
+            footer,
+        );
+
+        if self.confirming {
+            self.draw_confirm(frame);
+        }
+    }
+
+    fn row_line(&self, index: usize, row: &Row) -> Line<'static> {
+        match row {
+            Row::Category {
+                category,
+                count,
+                size,
+            } => Line::from(format!(
+                "{} ({} items, {})",
+                category,
+                count,
+                format_size(*size, BINARY)
+            ))
+            .bold()
+            .cyan(),
+            Row::Item(item) => {
+                let mark = if self.marked.contains(&index) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                Line::from(vec![
+                    Span::raw(format!("  {} ", mark)),
+                    Span::raw(format!("{:>12} ", scanner::format_item_size(item))),
+                    Span::styled(
+                        format!("{:<8} ", item.risk_level),
+                        Style::default().fg(risk_color(item.risk_level)),
+                    ),
+                    Span::raw(item.path.clone()),
+                ])
+            }
+        }
+    }
+
+    fn draw_confirm(&self, frame: &mut Frame) {
+        let [area] = Layout::vertical([Constraint::Length(4)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::horizontal([Constraint::Percentage(60)])
+            .flex(Flex::Center)
+            .areas(area);
+
+        let text = vec![
+            Line::from(format!(
+                "Permanently delete {} items ({})?",
+                self.marked.len(),
+                format_size(self.marked_size(), BINARY)
+            ))
+            .bold(),
+            Line::from("y to delete, any other key to go back"),
+        ];
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(text).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(" Confirm ")
+                    .red(),
+            ),
+            area,
+        );
+    }
+}
+
+fn risk_color(risk: RiskLevel) -> Color {
+    match risk {
+        RiskLevel::Safe => Color::Green,
+        RiskLevel::Moderate => Color::Yellow,
+        RiskLevel::Risky => Color::Red,
+        RiskLevel::Manual => Color::Magenta,
+    }
+}
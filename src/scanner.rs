@@ -1,22 +1,145 @@
 use crate::types::*;
-use anyhow::Result;
+use crate::{cache, paths};
+use anyhow::{bail, Context, Result};
 use colored::Colorize;
+use globset::{Glob, GlobSetBuilder};
 use humansize::{format_size, BINARY};
-use indicatif::{ProgressBar, ProgressStyle};
 use rayon::prelude::*;
 use regex::Regex;
-use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::Read;
+use std::io::{self, Read, Write};
+use std::os::unix::fs::MetadataExt;
 use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use walkdir::WalkDir;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use walkdir::{DirEntry, WalkDir};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Forwards scan progress to an optional callback. Items found by a phase are
+/// reported once the phase finishes.
+struct Progress<'a> {
+    callback: Option<&'a dyn Fn(ScanEvent)>,
+    reported: usize,
+    stats: &'a ScanStats,
+    /// The running phase: name, start time, items found before it and the
+    /// counters when it started
+    current: Option<(String, Instant, usize, ScanStatsSnapshot)>,
+}
+
+impl Progress<'_> {
+    fn emit(&self, event: ScanEvent) {
+        if let Some(callback) = self.callback {
+            callback(event);
+        }
+    }
+
+    fn report_new_items(&mut self, items: &Arc<Mutex<Vec<CleanableItem>>>) {
+        if self.callback.is_none() {
+            return;
+        }
+        let items = items.lock().unwrap();
+        for item in &items[self.reported..] {
+            self.emit(ScanEvent::ItemFound(item.clone()));
+        }
+        self.reported = items.len();
+    }
+
+    fn finish_phase(&mut self, items: &Arc<Mutex<Vec<CleanableItem>>>) {
+        let Some((phase, started, first_item, before)) = self.current.take() else {
+            return;
+        };
+        if self.callback.is_none() {
+            return;
+        }
+
+        let items = items.lock().unwrap();
+        let found = &items[first_item.min(items.len())..];
+        let after = self.stats.snapshot();
+        let profile = PhaseProfile {
+            phase,
+            elapsed: started.elapsed(),
+            items: found.len(),
+            bytes_found: found.iter().map(|item| item.size).sum(),
+            sizing: Duration::from_nanos(after.sizing_nanos - before.sizing_nanos),
+            dirs_sized: after.dirs_sized - before.dirs_sized,
+            bytes_hashed: after.bytes_hashed - before.bytes_hashed,
+        };
+        drop(items);
+        self.emit(ScanEvent::PhaseFinished(profile));
+    }
+
+    fn phase(&mut self, name: impl Into<String>, items: &Arc<Mutex<Vec<CleanableItem>>>) {
+        self.report_new_items(items);
+        self.finish_phase(items);
+
+        let name = name.into();
+        let first_item = items.lock().unwrap().len();
+        self.current = Some((
+            name.clone(),
+            Instant::now(),
+            first_item,
+            self.stats.snapshot(),
+        ));
+        self.emit(ScanEvent::PhaseStarted(name));
+    }
+
+    fn finish(&mut self, items: &Arc<Mutex<Vec<CleanableItem>>>) {
+        self.report_new_items(items);
+        self.finish_phase(items);
+        self.emit(ScanEvent::ScanComplete);
+    }
+}
+
+/// Work counters shared by every thread of a scan, for `PhaseProfile`s
+#[derive(Debug, Default)]
+struct ScanStats {
+    sizing_nanos: AtomicU64,
+    dirs_sized: AtomicU64,
+    bytes_hashed: AtomicU64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ScanStatsSnapshot {
+    sizing_nanos: u64,
+    dirs_sized: u64,
+    bytes_hashed: u64,
+}
+
+impl ScanStats {
+    fn snapshot(&self) -> ScanStatsSnapshot {
+        ScanStatsSnapshot {
+            sizing_nanos: self.sizing_nanos.load(Ordering::Relaxed),
+            dirs_sized: self.dirs_sized.load(Ordering::Relaxed),
+            bytes_hashed: self.bytes_hashed.load(Ordering::Relaxed),
+        }
+    }
+
+    fn record_sizing(&self, started: Instant) {
+        self.sizing_nanos
+            .fetch_add(started.elapsed().as_nanos() as u64, Ordering::Relaxed);
+        self.dirs_sized.fetch_add(1, Ordering::Relaxed);
+    }
+}
 
-pub fn scan(config: ScanConfig) -> Result<ScanResults> {
+/// Run every enabled detector over the configured paths. Pass a `progress`
+/// callback to receive [`ScanEvent`]s; without one the scan is silent.
+pub fn scan(mut config: ScanConfig, progress: Option<&dyn Fn(ScanEvent)>) -> Result<ScanResults> {
+    let started = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
     let items = Arc::new(Mutex::new(Vec::new()));
+    let mut duplicate_groups = Vec::new();
+    let stats = ScanStats::default();
+    let mut progress = Progress {
+        callback: progress,
+        reported: 0,
+        stats: &stats,
+        current: None,
+    };
 
-    println!("{}", "Starting dynamic filesystem scan...".cyan());
+    config.paths = validate_paths(&config.paths, config.strict_paths, &progress)?;
 
     // Determine max depth based on speed
     let max_depth = config.max_depth.unwrap_or(match config.speed {
@@ -25,46 +148,181 @@ pub fn scan(config: ScanConfig) -> Result<ScanResults> {
         ScanSpeed::Thorough => usize::MAX,
     });
 
-    let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.green} {msg}")
-            .unwrap(),
-    );
+    // An incremental scan doesn't search subtrees unchanged since the previous
+    // scan, and carries their items forward instead
+    let (unchanged, carried) = match &config.previous {
+        Some(previous) => unchanged_subtrees(&config.paths, previous),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let walk = WalkOptions {
+        follow_links: config.follow_symlinks,
+        estimate: config.estimate,
+        apparent: config.apparent_size,
+        dereference: config.dereference_size,
+        unchanged: &unchanged,
+        stats: &stats,
+    };
+
+    let enabled = |phase: ScanPhase| config.enabled_phases.contains(&phase);
+    let min_dir_size = config.min_dir_size_mb * 1024 * 1024;
 
     // 1. Scan for cache directories
-    pb.set_message("Scanning for cache directories...");
-    scan_cache_directories(&config.paths, max_depth, &items)?;
+    if enabled(ScanPhase::Caches) {
+        progress.phase("Scanning for cache directories...", &items);
+        scan_cache_directories(&config.paths, max_depth, min_dir_size, walk, &items)?;
+        scan_browser_caches(&config.paths, min_dir_size, walk, &items)?;
+        if config
+            .paths
+            .iter()
+            .any(|p| Path::new(p).join(HOMEBREW_CACHE_DIR).is_dir())
+        {
+            scan_homebrew(&config.paths, min_dir_size, walk, &items)?;
+        }
+        scan_python_caches(&config.paths, min_dir_size, walk, &items)?;
+    }
 
     // 2. Scan for build artifacts
-    pb.set_message("Scanning for build artifacts...");
-    scan_build_artifacts(&config.paths, max_depth, &items)?;
+    if enabled(ScanPhase::BuildArtifacts) {
+        progress.phase("Scanning for build artifacts...", &items);
+        scan_build_artifacts(
+            &config.paths,
+            max_depth,
+            min_dir_size,
+            config.skip_dirty_repos,
+            config.repo_activity,
+            walk,
+            &items,
+        )?;
+        scan_package_stores(&config.paths, min_dir_size, walk, &items)?;
+    }
 
     // 3. Scan for log files
-    pb.set_message("Scanning for log files...");
-    scan_log_files(&config.paths, max_depth, &items)?;
+    if enabled(ScanPhase::Logs) {
+        progress.phase("Scanning for log files...", &items);
+        scan_log_files(
+            &config.paths,
+            max_depth,
+            config.log_min_size_mb * 1024 * 1024,
+            walk,
+            &items,
+        )?;
+    }
 
     // 4. Scan for large files
-    if config.min_file_size_mb > 0 {
-        pb.set_message(format!(
-            "Scanning for files larger than {}MB...",
-            config.min_file_size_mb
-        ));
-        scan_large_files(&config.paths, max_depth, config.min_file_size_mb, &items)?;
+    if enabled(ScanPhase::LargeFiles) && config.min_file_size_mb > 0 {
+        progress.phase(
+            format!(
+                "Scanning for files larger than {}MB...",
+                config.min_file_size_mb
+            ),
+            &items,
+        );
+        scan_large_files(
+            &config.paths,
+            max_depth,
+            config.min_file_size_mb,
+            &ExtensionFilter::new(&config.extensions, &config.excluded_extensions),
+            walk,
+            &items,
+        )?;
     }
 
     // 5. Find duplicates
-    if config.find_duplicates {
-        pb.set_message("Finding duplicate files...");
-        find_duplicates(&config.paths, max_depth, &items)?;
+    if enabled(ScanPhase::Duplicates) && config.find_duplicates {
+        progress.phase("Finding duplicate files...", &items);
+        duplicate_groups = find_duplicates(
+            &config.paths,
+            max_depth,
+            config.dup_min_size_kb * 1024,
+            config.hash_threads,
+            walk,
+            &items,
+            &progress,
+        )?;
+    }
+
+    // 6. Scan the Trash and old downloads
+    if enabled(ScanPhase::TrashAndDownloads) {
+        progress.phase("Scanning Trash and Downloads...", &items);
+        scan_trash_and_downloads(&config.paths, min_dir_size, walk, &items)?;
+    }
+
+    // 7. Scan for Xcode build data and simulators
+    if enabled(ScanPhase::Xcode)
+        && config
+            .paths
+            .iter()
+            .any(|p| Path::new(p).join("Library/Developer").exists())
+    {
+        progress.phase("Scanning for Xcode artifacts...", &items);
+        scan_xcode_artifacts(&config.paths, min_dir_size, walk, &items)?;
+    }
+
+    // 8. Scan for Docker Desktop data
+    if enabled(ScanPhase::Docker)
+        && config
+            .paths
+            .iter()
+            .any(|p| Path::new(p).join(DOCKER_DATA_DIR).exists())
+    {
+        progress.phase("Scanning for Docker data...", &items);
+        scan_docker_data(&config.paths, min_dir_size, walk, &items)?;
+    }
+
+    // 9. Scan Mail's attachment caches
+    if enabled(ScanPhase::Mail)
+        && config
+            .paths
+            .iter()
+            .any(|p| Path::new(p).join("Library/Mail").exists())
+    {
+        progress.phase("Scanning Mail attachments...", &items);
+        scan_mail_attachments(&config.paths, min_dir_size, walk, &items)?;
+    }
+
+    // 10. Scan iOS device backups
+    if enabled(ScanPhase::DeviceBackups)
+        && config
+            .paths
+            .iter()
+            .any(|p| Path::new(p).join(DEVICE_BACKUP_DIR).is_dir())
+    {
+        progress.phase("Scanning iOS device backups...", &items);
+        scan_device_backups(&config.paths, walk, &items)?;
+    }
+
+    // 11. Scan for .DS_Store files and Quick Look thumbnails
+    if enabled(ScanPhase::Clutter) {
+        progress.phase("Scanning for Finder and Quick Look clutter...", &items);
+        scan_clutter(&config.paths, max_depth, min_dir_size, walk, &items)?;
+    }
+
+    // 12. Evaluate custom rules from the config file
+    if enabled(ScanPhase::CustomRules) && !config.custom_rules.is_empty() {
+        progress.phase("Applying custom rules...", &items);
+        scan_custom_rules(&config.paths, &config.custom_rules, max_depth, walk, &items)?;
+    }
+
+    if config.previous.is_some() {
+        progress.phase(
+            format!(
+                "Carrying forward {} items from {} unchanged directories...",
+                carried.len(),
+                unchanged.len()
+            ),
+            &items,
+        );
+        merge_carried_items(&items, carried);
     }
 
-    pb.finish_with_message("Scan complete!".green().to_string());
+    progress.finish(&items);
 
     let items = Arc::try_unwrap(items).unwrap().into_inner().unwrap();
 
     // Deduplicate nested paths to avoid double-counting
     let items = deduplicate_nested_paths(items);
+    let items = deduplicate_members(items, walk);
 
     let total_size: u64 = items.iter().map(|item| item.size).sum();
 
@@ -72,47 +330,301 @@ pub fn scan(config: ScanConfig) -> Result<ScanResults> {
         items,
         total_size,
         scan_speed: config.speed,
+        incremental: config.previous.is_some(),
+        min_file_size_mb: if enabled(ScanPhase::LargeFiles) {
+            config.min_file_size_mb
+        } else {
+            0
+        },
+        scanned_paths: config.paths,
+        timestamp: started,
+        duplicate_groups,
     })
 }
 
-fn deduplicate_nested_paths(items: Vec<CleanableItem>) -> Vec<CleanableItem> {
-    let mut sorted_items = items;
+/// Top-level directories of each scan path not modified since the previous
+/// scan, and the previous scan's items inside them
+fn unchanged_subtrees(
+    scan_paths: &[String],
+    previous: &PreviousScan,
+) -> (Vec<PathBuf>, Vec<CleanableItem>) {
+    let mut unchanged = Vec::new();
+
+    // Only what the previous scan covered can be carried forward
+    let covered = |base: &Path| {
+        previous
+            .scanned_paths
+            .iter()
+            .any(|scanned| base.starts_with(scanned))
+    };
+
+    for base_path in scan_paths.iter().filter(|p| covered(Path::new(p))) {
+        let Ok(entries) = fs::read_dir(base_path) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_dir() {
+                continue;
+            }
+            if modified_secs(&metadata).is_some_and(|modified| modified < previous.timestamp) {
+                unchanged.push(entry.path());
+            }
+        }
+    }
 
-    // Sort by path length (shortest first) so parent directories come before their children
-    sorted_items.sort_by(|a, b| a.path.len().cmp(&b.path.len()));
+    let carried = previous
+        .items
+        .iter()
+        .filter(|item| {
+            let path = Path::new(&item.path);
+            unchanged.iter().any(|dir| path.starts_with(dir))
+        })
+        .cloned()
+        .collect();
 
-    let mut deduplicated = Vec::new();
+    // Sorted for `walk_dir`'s skip list
+    unchanged.sort();
+    (unchanged, carried)
+}
 
-    for item in sorted_items {
-        let path = Path::new(&item.path);
+/// Add carried-forward items, unless a detector found the same path again
+fn merge_carried_items(items: &Arc<Mutex<Vec<CleanableItem>>>, carried: Vec<CleanableItem>) {
+    let mut items = items.lock().unwrap();
+    let fresh: HashSet<String> = items.iter().map(|item| item.path.clone()).collect();
+    items.extend(
+        carried
+            .into_iter()
+            .filter(|item| !fresh.contains(&item.path)),
+    );
+}
 
-        // Check if this item is a child of any already-kept item
-        let is_child = deduplicated.iter().any(|kept: &CleanableItem| {
-            let kept_path = Path::new(&kept.path);
-            // An item is a child if it starts with a kept path and is not the same path
-            path.starts_with(kept_path) && path != kept_path
-        });
+/// Report paths matching the user's custom rules. The first matching rule
+/// wins, and nothing inside a matched directory is considered again.
+fn scan_custom_rules(
+    paths: &[String],
+    rules: &[CustomRule],
+    max_depth: usize,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    let mut builder = GlobSetBuilder::new();
+    for rule in rules {
+        let pattern = paths::expand_path(&rule.pattern);
+        builder.add(
+            Glob::new(&pattern)
+                .with_context(|| format!("Invalid custom rule pattern '{}'", rule.pattern))?,
+        );
+    }
+    let globs = builder.build()?;
+
+    for base_path in paths {
+        let mut matched_dir: Option<PathBuf> = None;
+
+        for entry in walk_dir(
+            base_path,
+            max_depth,
+            walk.follow_links,
+            false,
+            walk.unchanged,
+        ) {
+            let path = entry.path();
+            if matched_dir
+                .as_ref()
+                .is_some_and(|dir| path.starts_with(dir))
+            {
+                continue;
+            }
+
+            let Some(&index) = globs.matches(path).first() else {
+                continue;
+            };
+            let rule = &rules[index];
+
+            let measured = if entry.file_type().is_dir() {
+                matched_dir = Some(path.to_path_buf());
+                match measure_dir(path, walk) {
+                    Ok(measured) => measured,
+                    Err(_) => continue,
+                }
+            } else {
+                match entry.metadata() {
+                    Ok(metadata) => DirSize::of_file(&metadata, walk),
+                    Err(_) => continue,
+                }
+            };
+
+            if measured.bytes == 0 {
+                continue;
+            }
+
+            items.lock().unwrap().push(CleanableItem {
+                path: path.display().to_string(),
+                members: Vec::new(),
+                size: measured.bytes,
+                estimated: measured.estimated,
+                apparent_size: measured.divergent_apparent(),
+                linked_size: measured.linked_size(),
+                category: rule.category.clone(),
+                risk_level: rule.risk,
+                description: rule
+                    .description
+                    .clone()
+                    .unwrap_or_else(|| format!("Custom rule: {}", rule.pattern)),
+                last_modified: get_modified_time(path),
+                project: None,
+                reason: Some(format!("matches custom rule `{}`", rule.pattern)),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Canonicalize scan paths, dropping (or rejecting, when strict) any that
+/// don't exist or aren't directories. They arrive already expanded: a second
+/// expansion would re-expand a `$` that came from a variable's value.
+fn validate_paths(scan_paths: &[String], strict: bool, progress: &Progress) -> Result<Vec<String>> {
+    let mut valid = Vec::new();
+
+    for path in scan_paths {
+        let problem = match fs::canonicalize(path) {
+            Ok(path) if path.is_dir() => {
+                valid.push(path.display().to_string());
+                continue;
+            }
+            Ok(_) => format!("Scan path is not a directory: {}", path),
+            Err(_) => format!("Scan path does not exist: {}", path),
+        };
+
+        if strict {
+            bail!(problem);
+        }
+        progress.emit(ScanEvent::Warning(format!("{}, skipping", problem)));
+    }
+
+    Ok(valid)
+}
+
+fn deduplicate_nested_paths(items: Vec<CleanableItem>) -> Vec<CleanableItem> {
+    // Collapse aliases (symlinked ancestors, `..`) so the same location can't
+    // be reported under two paths
+    let mut sorted_items: Vec<CleanableItem> = items
+        .into_iter()
+        .map(|mut item| {
+            item.path = paths::canonicalize_parent(Path::new(&item.path))
+                .display()
+                .to_string();
+            item
+        })
+        .collect();
+
+    // Sort by path components so every directory comes immediately before its
+    // descendants (`/a/foo`, `/a/foo/x`, `/a/foobar`), unlike a string sort.
+    // A path found by several detectors keeps its most specific category.
+    sorted_items.sort_by(|a, b| {
+        Path::new(&a.path)
+            .cmp(Path::new(&b.path))
+            .then_with(|| category_specificity(&b.category).cmp(&category_specificity(&a.category)))
+    });
+
+    let mut deduplicated: Vec<CleanableItem> = Vec::new();
 
-        // Only keep items that are not children of already-kept items
-        if !is_child {
+    for item in sorted_items {
+        // Descendants of a kept item (and repeats of it) form a contiguous run
+        // right after it, so comparing against the last kept item is enough.
+        // The outermost item always wins whatever its category, and is kept
+        // with its full size: a `.cache` inside a reported `node_modules` is
+        // simply dropped.
+        let is_covered = deduplicated
+            .last()
+            .is_some_and(|kept| Path::new(&item.path).starts_with(Path::new(&kept.path)));
+
+        if !is_covered {
             deduplicated.push(item);
         }
     }
 
+    // In component order, no item being nested inside (or equal to) any other
+    // reduces to no item being nested inside its predecessor
+    debug_assert!(deduplicated.windows(2).all(|pair| {
+        let (prev, next) = (Path::new(&pair[0].path), Path::new(&pair[1].path));
+        !next.starts_with(prev)
+    }));
+
     deduplicated
 }
 
+/// Drop aggregate members (a `.DS_Store`, a `__pycache__`) that lie inside an
+/// ordinary item, which deletes them anyway, and take their size off the
+/// aggregate. Needed after every phase has run: a custom rule or build
+/// artifact reported late can cover members collected earlier. An aggregate
+/// left without members is dropped.
+fn deduplicate_members(items: Vec<CleanableItem>, walk: WalkOptions) -> Vec<CleanableItem> {
+    let covering: Vec<PathBuf> = items
+        .iter()
+        .filter(|item| item.members.is_empty())
+        .map(|item| PathBuf::from(&item.path))
+        .collect();
+
+    items
+        .into_iter()
+        .filter_map(|mut item| {
+            if item.members.is_empty() {
+                return Some(item);
+            }
+
+            let mut dropped = DirSize::default();
+            item.members.retain(|member| {
+                let covered = covering
+                    .iter()
+                    .any(|dir| Path::new(member).starts_with(dir));
+                if covered {
+                    dropped.add(measure_path(Path::new(member), walk));
+                }
+                !covered
+            });
+            if item.members.is_empty() {
+                return None;
+            }
+
+            item.size = item.size.saturating_sub(dropped.bytes);
+            item.apparent_size = item
+                .apparent_size
+                .map(|apparent| apparent.saturating_sub(dropped.apparent_bytes));
+            item.linked_size = item
+                .linked_size
+                .map(|linked| linked.saturating_sub(dropped.bytes + dropped.linked_bytes));
+            Some(item)
+        })
+        .collect()
+}
+
+/// How much a category says about an item beyond its size. When two
+/// detectors report the same path the higher one wins: a large file that is
+/// also a duplicate is reported as a duplicate.
+fn category_specificity(category: &CleanCategory) -> u8 {
+    match category {
+        CleanCategory::LargeFiles => 0,
+        CleanCategory::OldDownloads => 1,
+        CleanCategory::DuplicateFiles => 2,
+        // The user asked for these explicitly
+        CleanCategory::Custom(_) => 4,
+        _ => 3,
+    }
+}
+
 fn scan_cache_directories(
     paths: &[String],
     max_depth: usize,
+    min_dir_size: u64,
+    walk: WalkOptions,
     items: &Arc<Mutex<Vec<CleanableItem>>>,
 ) -> Result<()> {
-    let cache_patterns = [
-        r"(?i)cache$",
-        r"(?i)\.cache$",
-        r"(?i)caches$",
-        r"Library/Caches",
-    ];
+    // Matched against the directory's own name, never the whole path
+    let cache_patterns = [r"(?i)cache$", r"(?i)caches$"];
 
     let regexes: Vec<Regex> = cache_patterns
         .iter()
@@ -120,12 +632,24 @@ fn scan_cache_directories(
         .collect();
 
     for base_path in paths {
-        for entry in WalkDir::new(base_path)
-            .max_depth(max_depth)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+        let dedicated_roots: Vec<PathBuf> = BROWSER_CACHES
+            .iter()
+            .map(|browser| browser.root)
+            .chain([HOMEBREW_CACHE_DIR, QUICKLOOK_CACHE_DIR])
+            .map(|root| Path::new(base_path).join(root))
+            .collect();
+        let python_roots: Vec<PathBuf> = PYTHON_TOOL_DIRS
+            .iter()
+            .map(|dir| Path::new(base_path).join(dir))
+            .collect();
+
+        for entry in walk_dir(
+            base_path,
+            max_depth,
+            walk.follow_links,
+            false,
+            walk.unchanged,
+        ) {
             if !entry.file_type().is_dir() {
                 continue;
             }
@@ -138,31 +662,396 @@ fn scan_cache_directories(
                 continue;
             }
 
-            for regex in &regexes {
-                if regex.is_match(&path_str) {
-                    if let Ok(size) = get_dir_size(path) {
-                        if size > 1024 * 1024 {
-                            // > 1MB
-                            let category = categorize_cache(path);
-                            let risk = match category {
-                                CleanCategory::SystemCache => RiskLevel::Safe,
-                                CleanCategory::BrowserCache => RiskLevel::Safe,
-                                _ => RiskLevel::Safe,
-                            };
+            // Report each app's cache separately rather than the whole
+            // container, so per-profile browser caches aren't swallowed by it
+            if path.ends_with("Library/Caches") {
+                continue;
+            }
 
-                            items.lock().unwrap().push(CleanableItem {
-                                path: path.display().to_string(),
-                                size,
-                                category,
-                                risk_level: risk,
-                                description: format!(
-                                    "Cache directory: {}",
-                                    path.file_name().unwrap_or_default().to_string_lossy()
-                                ),
-                            });
+            // Browser caches are reported per profile by scan_browser_caches,
+            // Homebrew's by scan_homebrew and Quick Look's by scan_clutter
+            if dedicated_roots
+                .iter()
+                .any(|root| root.starts_with(path) || path.starts_with(root))
+            {
+                continue;
+            }
+
+            // Python tool caches are reported by scan_python_caches
+            if python_roots.iter().any(|root| path.starts_with(root)) {
+                continue;
+            }
+
+            // Each app's folder in Library/Caches is a cache whatever its
+            // name; elsewhere the directory's name has to say so
+            let reason = if path
+                .parent()
+                .is_some_and(|parent| parent.ends_with("Library/Caches"))
+            {
+                "app folder in Library/Caches".to_string()
+            } else {
+                let dir_name = entry.file_name().to_string_lossy();
+                let Some(regex) = regexes.iter().find(|regex| regex.is_match(&dir_name)) else {
+                    continue;
+                };
+                // A project that merely has "cache" in its name
+                if PROJECT_MARKERS
+                    .iter()
+                    .any(|marker| path.join(marker).exists())
+                {
+                    continue;
+                }
+                format!(
+                    "directory name matches cache pattern `{}`",
+                    regex.as_str().trim_start_matches("(?i)")
+                )
+            };
+
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(path, walk)
+            {
+                // A cache of symlinks into a content store is worth listing
+                // when its targets are what it would regenerate
+                let linked = measured.linked_size().unwrap_or(size);
+                if size > min_dir_size || linked > min_dir_size {
+                    let category = categorize_cache(path);
+                    let risk = match category {
+                        CleanCategory::SystemCache => RiskLevel::Safe,
+                        CleanCategory::BrowserCache => RiskLevel::Safe,
+                        _ => RiskLevel::Safe,
+                    };
+
+                    items.lock().unwrap().push(CleanableItem {
+                        path: path.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category,
+                        risk_level: risk,
+                        description: format!(
+                            "Cache directory: {}",
+                            path.file_name().unwrap_or_default().to_string_lossy()
+                        ),
+                        last_modified: get_modified_time(path),
+                        project: None,
+                        reason: Some(reason),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Homebrew's cache, holding downloaded bottles and casks among other things
+const HOMEBREW_CACHE_DIR: &str = "Library/Caches/Homebrew";
+
+fn scan_homebrew(
+    paths: &[String],
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    let mut cache_dirs = Vec::new();
+    for base_path in paths {
+        let cache_dir = Path::new(base_path).join(HOMEBREW_CACHE_DIR);
+        let downloads = cache_dir.join("downloads");
+        cache_dirs.push(cache_dir);
+
+        if let Ok(
+            measured @ DirSize {
+                bytes: size,
+                estimated,
+                ..
+            },
+        ) = measure_dir(&downloads, walk)
+        {
+            if size > min_dir_size {
+                items.lock().unwrap().push(CleanableItem {
+                    path: downloads.display().to_string(),
+                    members: Vec::new(),
+                    size,
+                    estimated,
+                    apparent_size: measured.divergent_apparent(),
+                    linked_size: measured.linked_size(),
+                    category: CleanCategory::BrewCache,
+                    risk_level: RiskLevel::Safe,
+                    description: "Homebrew bottle and cask downloads".to_string(),
+                    last_modified: get_modified_time(&downloads),
+                    project: None,
+                    reason: Some(format!(
+                        "Homebrew's downloads folder ({}/downloads)",
+                        HOMEBREW_CACHE_DIR
+                    )),
+                });
+            }
+        }
+    }
+
+    // Outdated versions in the Cellar are only safe to remove through brew
+    // itself; the downloads above already cover its cache
+    for path in brew_cleanup_candidates().unwrap_or_default() {
+        if cache_dirs.iter().any(|dir| path.starts_with(dir)) {
+            continue;
+        }
+        let Ok(
+            measured @ DirSize {
+                bytes: size,
+                estimated,
+                ..
+            },
+        ) = measure_dir(&path, walk)
+        else {
+            continue;
+        };
+        if size > min_dir_size {
+            items.lock().unwrap().push(CleanableItem {
+                path: path.display().to_string(),
+                members: Vec::new(),
+                size,
+                estimated,
+                apparent_size: measured.divergent_apparent(),
+                linked_size: measured.linked_size(),
+                category: CleanCategory::BrewCache,
+                risk_level: RiskLevel::Moderate,
+                description: "Outdated Homebrew package (removed by `brew cleanup`)".to_string(),
+                last_modified: get_modified_time(&path),
+                project: None,
+                reason: Some("listed by `brew cleanup --dry-run`".to_string()),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Paths `brew cleanup --dry-run` says it would remove. Returns None when
+/// Homebrew isn't installed or the command fails.
+fn brew_cleanup_candidates() -> Option<Vec<PathBuf>> {
+    let output = Command::new("brew")
+        .args(["cleanup", "--dry-run"])
+        .env("HOMEBREW_NO_AUTO_UPDATE", "1")
+        .env("HOMEBREW_NO_ENV_HINTS", "1")
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    // Lines look like "Would remove: /opt/homebrew/Cellar/foo/1.2 (42 files, 1.5MB)"
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let paths = stdout
+        .lines()
+        .filter_map(|line| line.strip_prefix("Would remove: "))
+        .map(|rest| match rest.rfind(" (") {
+            Some(index) => &rest[..index],
+            None => rest,
+        })
+        .map(PathBuf::from)
+        .collect();
+
+    Some(paths)
+}
+
+/// Directories of Python package tools the generic cache scan leaves to
+/// scan_python_caches. Poetry keeps virtualenvs next to its caches, which
+/// mustn't be reported as cache.
+const PYTHON_TOOL_DIRS: &[&str] = &[
+    "Library/Caches/pip",
+    "Library/Caches/pipenv",
+    "Library/Caches/pypoetry",
+    "Library/Caches/uv",
+];
+
+/// Caches of Python package tools, relative to the home folder
+const PYTHON_CACHES: &[(&str, &str)] = &[
+    ("Library/Caches/pip", "pip download and wheel cache"),
+    (".cache/pip", "pip download and wheel cache"),
+    ("Library/Caches/pipenv", "Pipenv package cache"),
+    ("Library/Caches/pypoetry/cache", "Poetry package cache"),
+    (
+        "Library/Caches/pypoetry/artifacts",
+        "Poetry downloaded artifacts",
+    ),
+    (".cache/pypoetry/cache", "Poetry package cache"),
+    (".cache/pypoetry/artifacts", "Poetry downloaded artifacts"),
+    ("Library/Caches/uv", "uv package cache"),
+    (".cache/uv", "uv package cache"),
+];
+
+fn scan_python_caches(
+    paths: &[String],
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    for base_path in paths {
+        for (location, description) in PYTHON_CACHES {
+            let path = Path::new(base_path).join(location);
+            if !path.is_dir() {
+                continue;
+            }
+
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&path, walk)
+            {
+                if size > min_dir_size {
+                    items.lock().unwrap().push(CleanableItem {
+                        path: path.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category: CleanCategory::PipCache,
+                        risk_level: RiskLevel::Safe,
+                        description: description.to_string(),
+                        last_modified: get_modified_time(&path),
+                        project: None,
+                        reason: Some(format!("known Python tool cache location ~/{}", location)),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Where a browser keeps its per-profile caches
+enum ProfileLayout {
+    /// Every child of the root is a profile directory holding only caches
+    ProfileDirs,
+    /// Every child of the root is a profile with its cache in this subdirectory
+    ProfileSubdir(&'static str),
+    /// The root is a single cache with no profiles
+    Single,
+}
+
+struct BrowserCache {
+    name: &'static str,
+    root: &'static str,
+    layout: ProfileLayout,
+}
+
+const BROWSER_CACHES: &[BrowserCache] = &[
+    BrowserCache {
+        name: "Chrome",
+        root: "Library/Caches/Google/Chrome",
+        layout: ProfileLayout::ProfileDirs,
+    },
+    BrowserCache {
+        name: "Chromium",
+        root: "Library/Caches/Chromium",
+        layout: ProfileLayout::ProfileDirs,
+    },
+    BrowserCache {
+        name: "Edge",
+        root: "Library/Caches/Microsoft Edge",
+        layout: ProfileLayout::ProfileDirs,
+    },
+    BrowserCache {
+        name: "Brave",
+        root: "Library/Caches/BraveSoftware/Brave-Browser",
+        layout: ProfileLayout::ProfileDirs,
+    },
+    BrowserCache {
+        name: "Firefox",
+        root: "Library/Caches/Firefox/Profiles",
+        layout: ProfileLayout::ProfileSubdir("cache2"),
+    },
+    BrowserCache {
+        name: "Safari",
+        root: "Library/Caches/com.apple.Safari",
+        layout: ProfileLayout::Single,
+    },
+];
+
+fn scan_browser_caches(
+    paths: &[String],
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    for base_path in paths {
+        for browser in BROWSER_CACHES {
+            let root = Path::new(base_path).join(browser.root);
+            if !root.is_dir() {
+                continue;
+            }
+
+            // (cache directory, profile name)
+            let mut caches: Vec<(PathBuf, Option<String>)> = Vec::new();
+            match browser.layout {
+                ProfileLayout::Single => caches.push((root, None)),
+                ProfileLayout::ProfileDirs | ProfileLayout::ProfileSubdir(_) => {
+                    let Ok(entries) = fs::read_dir(&root) else {
+                        continue;
+                    };
+                    for entry in entries.filter_map(|e| e.ok()) {
+                        let profile = entry.path();
+                        if !profile.is_dir() {
+                            continue;
+                        }
+                        let name = entry.file_name().to_string_lossy().into_owned();
+                        let cache = match browser.layout {
+                            ProfileLayout::ProfileSubdir(subdir) => profile.join(subdir),
+                            _ => profile,
+                        };
+                        if cache.is_dir() {
+                            caches.push((cache, Some(name)));
                         }
                     }
-                    break;
+                }
+            }
+
+            for (path, profile) in caches {
+                if let Ok(
+                    measured @ DirSize {
+                        bytes: size,
+                        estimated,
+                        ..
+                    },
+                ) = measure_dir(&path, walk)
+                {
+                    if size > min_dir_size {
+                        let description = match profile {
+                            Some(profile) => {
+                                format!("{} cache (profile: {})", browser.name, profile)
+                            }
+                            None => format!("{} cache", browser.name),
+                        };
+                        items.lock().unwrap().push(CleanableItem {
+                            path: path.display().to_string(),
+                            members: Vec::new(),
+                            size,
+                            estimated,
+                            apparent_size: measured.divergent_apparent(),
+                            linked_size: measured.linked_size(),
+                            category: CleanCategory::BrowserCache,
+                            risk_level: RiskLevel::Safe,
+                            description,
+                            last_modified: get_modified_time(&path),
+                            project: None,
+                            reason: Some(format!(
+                                "{} keeps its caches in ~/{}",
+                                browser.name, browser.root
+                            )),
+                        });
+                    }
                 }
             }
         }
@@ -174,8 +1063,15 @@ fn scan_cache_directories(
 fn scan_build_artifacts(
     paths: &[String],
     max_depth: usize,
+    min_dir_size: u64,
+    skip_dirty_repos: bool,
+    repo_activity: bool,
+    walk: WalkOptions,
     items: &Arc<Mutex<Vec<CleanableItem>>>,
 ) -> Result<()> {
+    // Git state per repository root, so each repo is only asked once
+    let mut repos: HashMap<PathBuf, Option<RepoState>> = HashMap::new();
+
     let artifact_patterns = vec![
         (
             "node_modules",
@@ -191,11 +1087,6 @@ fn scan_build_artifacts(
             RiskLevel::Moderate,
         ),
         (".maven", CleanCategory::BuildArtifacts, RiskLevel::Moderate),
-        (
-            "__pycache__",
-            CleanCategory::BuildArtifacts,
-            RiskLevel::Safe,
-        ),
         (
             ".pytest_cache",
             CleanCategory::BuildArtifacts,
@@ -207,17 +1098,38 @@ fn scan_build_artifacts(
     ];
 
     for base_path in paths {
-        for entry in WalkDir::new(base_path)
-            .max_depth(max_depth)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+        // Python bytecode is scattered in small pieces, so it's gathered
+        // into one item per scan path: (path, size, last modified)
+        let mut bytecode: Vec<(PathBuf, DirSize, Option<u64>)> = Vec::new();
+
+        for entry in walk_dir(
+            base_path,
+            max_depth,
+            walk.follow_links,
+            false,
+            walk.unchanged,
+        ) {
+            let path = entry.path();
+
+            // Python 2 left its .pyc files next to the sources
+            if entry.file_type().is_file() {
+                if path.extension().is_some_and(|ext| ext == "pyc")
+                    && path.with_extension("py").exists()
+                {
+                    if let Ok(metadata) = entry.metadata() {
+                        bytecode.push((
+                            path.to_path_buf(),
+                            DirSize::of_file(&metadata, walk),
+                            modified_secs(&metadata),
+                        ));
+                    }
+                }
+                continue;
+            }
             if !entry.file_type().is_dir() {
                 continue;
             }
 
-            let path = entry.path();
             let path_str = path.to_string_lossy();
 
             // Skip our own target directory
@@ -227,39 +1139,139 @@ fn scan_build_artifacts(
 
             let dir_name = path.file_name().unwrap_or_default().to_string_lossy();
 
+            // Recognized by what's inside, whatever the directory is called
+            if is_virtualenv(path) {
+                if let Ok(measured) = measure_dir(path, walk) {
+                    if measured.bytes > min_dir_size {
+                        items.lock().unwrap().push(virtualenv_item(path, measured));
+                    }
+                }
+                continue;
+            }
+
+            if dir_name == "__pycache__" {
+                let dirty = skip_dirty_repos
+                    && path
+                        .parent()
+                        .and_then(find_repo_root)
+                        .and_then(|root| {
+                            repos
+                                .entry(root.clone())
+                                .or_insert_with(|| {
+                                    repo_state(&root, skip_dirty_repos, repo_activity)
+                                })
+                                .clone()
+                        })
+                        .is_some_and(|repo| repo.dirty);
+                if !dirty {
+                    if let Ok(measured) = measure_dir(path, walk) {
+                        bytecode.push((path.to_path_buf(), measured, get_modified_time(path)));
+                    }
+                }
+                continue;
+            }
+
             for (pattern, category, risk) in &artifact_patterns {
                 if dir_name == *pattern {
+                    let mut reason = format!("directory named `{}`", pattern);
+
                     // Special handling for 'target' - check if it's a Rust project
                     if *pattern == "target" {
                         if let Some(parent) = path.parent() {
                             if !parent.join("Cargo.toml").exists() {
                                 continue;
                             }
+                            reason = "`target` next to Cargo.toml".to_string();
                         }
                     }
 
                     // Special handling for 'build', 'dist', 'out' - check for project files
                     if *pattern == "build" || *pattern == "dist" || *pattern == "out" {
                         if let Some(parent) = path.parent() {
-                            let has_project_file = parent.join("package.json").exists()
-                                || parent.join("build.gradle").exists()
-                                || parent.join("pom.xml").exists()
-                                || parent.join("go.mod").exists();
+                            let project_file =
+                                ["package.json", "build.gradle", "pom.xml", "go.mod"]
+                                    .into_iter()
+                                    .find(|file| parent.join(file).exists());
 
-                            if !has_project_file {
+                            let Some(project_file) = project_file else {
                                 continue;
-                            }
+                            };
+                            reason = format!("`{}` next to {}", pattern, project_file);
                         }
                     }
 
-                    if let Ok(size) = get_dir_size(path) {
-                        if size > 1024 * 1024 {
+                    let mut risk = *risk;
+                    let mut description = format!("{} directory", pattern);
+
+                    // git is only run when something asked about the repo
+                    let repo = (skip_dirty_repos || repo_activity)
+                        .then(|| path.parent().and_then(find_repo_root))
+                        .flatten()
+                        .and_then(|root| {
+                            repos
+                                .entry(root.clone())
+                                .or_insert_with(|| {
+                                    repo_state(&root, skip_dirty_repos, repo_activity)
+                                })
+                                .clone()
+                        });
+
+                    if skip_dirty_repos && repo.as_ref().is_some_and(|repo| repo.dirty) {
+                        break;
+                    }
+
+                    // Rebuilding is only a nuisance in a repo that is being
+                    // worked on; one without commits in months is fair game
+                    if let Some(days) = repo.and_then(|repo| repo.last_commit_days) {
+                        if days >= ABANDONED_PROJECT_DAYS {
+                            risk = RiskLevel::Safe;
+                            description = format!(
+                                "{} of abandoned repository (no commits for {} days)",
+                                pattern, days
+                            );
+                        } else if days < ACTIVE_REPO_DAYS && risk == RiskLevel::Moderate {
+                            risk = RiskLevel::Risky;
+                            description = format!(
+                                "{} of active repository (committed to {} days ago)",
+                                pattern, days
+                            );
+                        }
+                    } else if *pattern == "node_modules" {
+                        // node_modules of a project nobody has touched in
+                        // months is almost certainly safe to drop
+                        if let Some(idle_days) = path.parent().and_then(idle_days) {
+                            if idle_days >= ABANDONED_PROJECT_DAYS {
+                                risk = RiskLevel::Safe;
+                                description = format!(
+                                    "node_modules of abandoned project (untouched for {} days)",
+                                    idle_days
+                                );
+                            }
+                        }
+                    }
+
+                    if let Ok(
+                        measured @ DirSize {
+                            bytes: size,
+                            estimated,
+                            ..
+                        },
+                    ) = measure_dir(path, walk)
+                    {
+                        if size > min_dir_size {
                             items.lock().unwrap().push(CleanableItem {
                                 path: path.display().to_string(),
+                                members: Vec::new(),
                                 size,
-                                category: *category,
-                                risk_level: *risk,
-                                description: format!("{} directory", pattern),
+                                estimated,
+                                apparent_size: measured.divergent_apparent(),
+                                linked_size: measured.linked_size(),
+                                category: category.clone(),
+                                risk_level: risk,
+                                description,
+                                last_modified: get_modified_time(path),
+                                project: path.parent().and_then(project_name),
+                                reason: Some(reason),
                             });
                         }
                     }
@@ -267,58 +1279,260 @@ fn scan_build_artifacts(
                 }
             }
         }
+
+        // Bytecode inside something already reported goes with it
+        let reported: Vec<PathBuf> = items
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|item| PathBuf::from(&item.path))
+            .collect();
+        bytecode.retain(|(path, _, _)| !reported.iter().any(|dir| path.starts_with(dir)));
+
+        let mut measured = DirSize::default();
+        for (_, size, _) in &bytecode {
+            measured.add(*size);
+            measured.estimated |= size.estimated;
+        }
+        if !bytecode.is_empty() && measured.bytes > min_dir_size {
+            let directories = bytecode.iter().filter(|(path, _, _)| path.is_dir()).count();
+            let description = match bytecode.len() - directories {
+                0 => format!("Python bytecode: {} __pycache__ directories", directories),
+                stray => format!(
+                    "Python bytecode: {} __pycache__ directories, {} stray .pyc files",
+                    directories, stray
+                ),
+            };
+            items.lock().unwrap().push(CleanableItem {
+                path: Path::new(base_path)
+                    .join("**/__pycache__")
+                    .display()
+                    .to_string(),
+                members: bytecode
+                    .iter()
+                    .map(|(path, _, _)| path.display().to_string())
+                    .collect(),
+                size: measured.bytes,
+                estimated: measured.estimated,
+                apparent_size: measured.divergent_apparent(),
+                linked_size: measured.linked_size(),
+                category: CleanCategory::BuildArtifacts,
+                risk_level: RiskLevel::Safe,
+                description,
+                last_modified: bytecode
+                    .iter()
+                    .filter_map(|(_, _, modified)| *modified)
+                    .max(),
+                project: None,
+                reason: Some(
+                    "`__pycache__` directories and `.pyc` files next to their `.py` source, \
+                     regenerated on the next import"
+                        .to_string(),
+                ),
+            });
+        }
     }
 
     Ok(())
 }
 
-fn scan_log_files(
+/// Whether `dir` is a Python virtual environment. venv and virtualenv 20+
+/// write `pyvenv.cfg`; older virtualenvs have `bin/activate` and record
+/// the base interpreter in `lib/python*/orig-prefix.txt` (which tells them
+/// apart from a conda or Python install that also has `bin/activate`).
+fn is_virtualenv(dir: &Path) -> bool {
+    if dir.join("pyvenv.cfg").is_file() {
+        return true;
+    }
+    dir.join("bin/activate").is_file()
+        && fs::read_dir(dir.join("lib")).is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|entry| entry.path().join("orig-prefix.txt").is_file())
+        })
+}
+
+fn virtualenv_item(path: &Path, measured: DirSize) -> CleanableItem {
+    // Installing packages touches site-packages and bin, not the
+    // environment's own directory
+    let mut touched = vec![path.to_path_buf(), path.join("bin")];
+    if let Ok(entries) = fs::read_dir(path.join("lib")) {
+        touched.extend(entries.flatten().map(|e| e.path().join("site-packages")));
+    }
+    let last_modified = touched.iter().filter_map(|p| get_modified_time(p)).max();
+
+    // pyvenv.cfg records the interpreter, e.g. `version = 3.12.1`
+    let version = fs::read_to_string(path.join("pyvenv.cfg"))
+        .ok()
+        .and_then(|cfg| {
+            cfg.lines().find_map(|line| {
+                let (key, value) = line.split_once('=')?;
+                matches!(key.trim(), "version" | "version_info").then(|| value.trim().to_string())
+            })
+        });
+    let mut description = match version {
+        Some(version) => format!("Python {} virtualenv", version),
+        None => "Python virtualenv".to_string(),
+    };
+    if let Some(modified) = last_modified {
+        description.push_str(&format!(" (last modified {})", format_age(modified)));
+    }
+
+    CleanableItem {
+        path: path.display().to_string(),
+        members: Vec::new(),
+        size: measured.bytes,
+        estimated: measured.estimated,
+        apparent_size: measured.divergent_apparent(),
+        linked_size: measured.linked_size(),
+        category: CleanCategory::BuildArtifacts,
+        risk_level: RiskLevel::Moderate,
+        description,
+        last_modified,
+        project: path.parent().and_then(project_name),
+        reason: Some(if path.join("pyvenv.cfg").is_file() {
+            "directory containing pyvenv.cfg".to_string()
+        } else {
+            "directory containing bin/activate and lib/python*/orig-prefix.txt".to_string()
+        }),
+    }
+}
+
+/// Projects whose directory hasn't changed in this many days count as abandoned
+const ABANDONED_PROJECT_DAYS: u64 = 180;
+
+/// Repositories committed to within this many days count as actively developed
+const ACTIVE_REPO_DAYS: u64 = 7;
+
+#[derive(Debug, Clone)]
+struct RepoState {
+    /// Tracked files have uncommitted changes (only checked when asked for)
+    dirty: bool,
+    /// Whole days since the last commit, `None` for a repo without commits
+    /// (or when not asked for)
+    last_commit_days: Option<u64>,
+}
+
+/// Files that mark the root of a project
+const PROJECT_MARKERS: &[&str] = &[
+    "Cargo.toml",
+    "package.json",
+    "pyproject.toml",
+    "go.mod",
+    "build.gradle",
+    "pom.xml",
+    ".git",
+];
+
+/// Name of the nearest directory at or above `dir` that looks like a project root
+fn project_name(dir: &Path) -> Option<String> {
+    dir.ancestors()
+        .find(|ancestor| {
+            PROJECT_MARKERS
+                .iter()
+                .any(|marker| ancestor.join(marker).exists())
+        })
+        .and_then(|root| root.file_name())
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Walk up from `dir` to the root of the enclosing git work tree
+fn find_repo_root(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .find(|ancestor| ancestor.join(".git").exists())
+        .map(Path::to_path_buf)
+}
+
+/// Ask git about a repository, running only the commands needed for what
+/// was asked. Returns None when git isn't installed or doesn't recognise
+/// `root` as a work tree.
+fn repo_state(root: &Path, check_dirty: bool, check_activity: bool) -> Option<RepoState> {
+    let git = |args: &[&str]| {
+        Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(args)
+            .output()
+            .ok()
+    };
+
+    let last_commit_days = if check_activity {
+        let log = git(&["log", "-1", "--format=%ct"])?;
+        if log.status.success() {
+            let committed: u64 = String::from_utf8_lossy(&log.stdout).trim().parse().ok()?;
+            let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+            Some(now.saturating_sub(committed) / 86400)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let dirty = if check_dirty {
+        let status = git(&["status", "--porcelain", "--untracked-files=no"])?;
+        if !status.status.success() {
+            return None;
+        }
+        !status.stdout.is_empty()
+    } else {
+        false
+    };
+
+    Some(RepoState {
+        dirty,
+        last_commit_days,
+    })
+}
+
+/// Number of whole days since a path was last modified
+fn idle_days(path: &Path) -> Option<u64> {
+    let modified = get_modified_time(path)?;
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(now.saturating_sub(modified) / 86400)
+}
+
+fn scan_package_stores(
     paths: &[String],
-    _max_depth: usize,
+    min_dir_size: u64,
+    walk: WalkOptions,
     items: &Arc<Mutex<Vec<CleanableItem>>>,
 ) -> Result<()> {
-    let log_regex = Regex::new(r"\.log$").unwrap();
+    let store_locations = [
+        ("Library/pnpm/store", "pnpm content-addressable store"),
+        (".pnpm-store", "pnpm content-addressable store"),
+    ];
 
     for base_path in paths {
-        let log_paths = vec![
-            format!("{}/Library/Logs", base_path),
-            format!("{}/logs", base_path),
-            format!("{}/.logs", base_path),
-        ];
-
-        for log_path in log_paths {
-            if !Path::new(&log_path).exists() {
+        for (location, description) in &store_locations {
+            let path = Path::new(base_path).join(location);
+            if !path.is_dir() {
                 continue;
             }
 
-            for entry in WalkDir::new(&log_path)
-                .max_depth(3)
-                .follow_links(false)
-                .into_iter()
-                .filter_map(|e| e.ok())
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&path, walk)
             {
-                let path = entry.path();
-
-                if entry.file_type().is_file() && log_regex.is_match(&path.to_string_lossy()) {
-                    if let Ok(metadata) = fs::metadata(path) {
-                        let size = metadata.len();
-                        if size > 10 * 1024 * 1024 {
-                            items.lock().unwrap().push(CleanableItem {
-                                path: path.display().to_string(),
-                                size,
-                                category: if path.to_string_lossy().contains("Library/Logs") {
-                                    CleanCategory::SystemLogs
-                                } else {
-                                    CleanCategory::AppLogs
-                                },
-                                risk_level: RiskLevel::Safe,
-                                description: format!(
-                                    "Large log file ({})",
-                                    format_size(size, BINARY)
-                                ),
-                            });
-                        }
-                    }
+                if size > min_dir_size {
+                    items.lock().unwrap().push(CleanableItem {
+                        path: path.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category: CleanCategory::AppCache,
+                        risk_level: RiskLevel::Moderate,
+                        description: description.to_string(),
+                        last_modified: get_modified_time(&path),
+                        project: None,
+                        reason: Some(format!("known pnpm store location ~/{}", location)),
+                    });
                 }
             }
         }
@@ -327,54 +1541,92 @@ fn scan_log_files(
     Ok(())
 }
 
-fn scan_large_files(
+/// System-wide log directories, scanned along with a home folder's own logs.
+/// Most of their contents need root to delete, hence Moderate.
+const SYSTEM_LOG_DIRS: &[&str] = &["/Library/Logs", "/private/var/log"];
+
+/// Where to look for logs, as (directory, its depth below the scan path,
+/// category, risk)
+fn log_locations(paths: &[String]) -> Vec<(PathBuf, usize, CleanCategory, RiskLevel)> {
+    let mut log_dirs: Vec<(PathBuf, usize, CleanCategory, RiskLevel)> = Vec::new();
+    for base_path in paths {
+        for dir in ["Library/Logs", "logs", ".logs"] {
+            log_dirs.push((
+                Path::new(base_path).join(dir),
+                Path::new(dir).components().count(),
+                CleanCategory::AppLogs,
+                RiskLevel::Safe,
+            ));
+        }
+    }
+    // Only a scan of a home folder reaches for the system logs
+    if paths
+        .iter()
+        .any(|p| Path::new(p).join("Library/Logs").is_dir())
+    {
+        // Counted as deep as ~/Library/Logs
+        for dir in SYSTEM_LOG_DIRS {
+            log_dirs.push((
+                PathBuf::from(dir),
+                2,
+                CleanCategory::SystemLogs,
+                RiskLevel::Moderate,
+            ));
+        }
+    }
+
+    log_dirs
+}
+
+/// foo.log and foo.out, plus rotated (foo.log.1) and compressed
+/// (foo.log.1.gz) copies; matched against the file name only
+const LOG_FILE_PATTERN: &str = r"(?i)\.(log|out)(\.\d+)*(\.(gz|bz2|xz|zst|zip))?$";
+
+fn scan_log_files(
     paths: &[String],
     max_depth: usize,
-    min_size_mb: u64,
+    min_size: u64,
+    walk: WalkOptions,
     items: &Arc<Mutex<Vec<CleanableItem>>>,
 ) -> Result<()> {
-    let min_size = min_size_mb * 1024 * 1024;
+    let log_regex = Regex::new(LOG_FILE_PATTERN).unwrap();
 
-    let skip_dirs = [
-        "Library/Application Support",
-        "Library/Mobile Documents",
-        "Applications",
-        "/System",
-        "/Library",
-        "Library/Mail",
-    ];
+    for (log_dir, depth, category, risk) in log_locations(paths) {
+        if !log_dir.exists() || depth >= max_depth {
+            continue;
+        }
 
-    for base_path in paths {
-        for entry in WalkDir::new(base_path)
-            .max_depth(max_depth)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
+        // Depth counts from the scan path, as in every other phase, so a
+        // quick scan (depth 3) only sees the files directly inside
+        // ~/Library/Logs and the system log folders counted as deep as it.
+        // Unreadable entries are skipped.
+        for entry in walk_dir(&log_dir, max_depth - depth, walk.follow_links, false, &[]) {
             let path = entry.path();
-            let path_str = path.to_string_lossy();
-
-            if skip_dirs.iter().any(|skip| path_str.contains(skip)) {
-                continue;
-            }
 
-            if let Some(name) = path.file_name() {
-                let name_str = name.to_string_lossy();
-                if name_str.starts_with('.') && name_str != ".cache" {
-                    continue;
-                }
-            }
-
-            if entry.file_type().is_file() {
-                if let Ok(metadata) = entry.metadata() {
-                    let size = metadata.len();
-                    if size >= min_size {
+            if entry.file_type().is_file()
+                && log_regex.is_match(&entry.file_name().to_string_lossy())
+            {
+                if let Ok(metadata) = fs::metadata(path) {
+                    let measured = DirSize::of_file(&metadata, walk);
+                    let size = measured.bytes;
+                    if size > min_size {
                         items.lock().unwrap().push(CleanableItem {
                             path: path.display().to_string(),
+                            members: Vec::new(),
                             size,
-                            category: CleanCategory::LargeFiles,
-                            risk_level: RiskLevel::Risky,
-                            description: format!("Large file ({})", format_size(size, BINARY)),
+                            estimated: false,
+                            apparent_size: measured.divergent_apparent(),
+                            linked_size: measured.linked_size(),
+                            category: category.clone(),
+                            risk_level: risk,
+                            description: format!("Large log file ({})", format_size(size, BINARY)),
+                            last_modified: modified_secs(&metadata),
+                            project: None,
+                            reason: Some(format!(
+                                "log file (`.log`/`.out`, rotated or compressed) over {} in {}",
+                                format_size(min_size, BINARY),
+                                log_dir.display()
+                            )),
                         });
                     }
                 }
@@ -385,61 +1637,103 @@ fn scan_large_files(
     Ok(())
 }
 
-fn find_duplicates(
+/// Simulator devices untouched for this many days are reported as stale
+const STALE_SIMULATOR_DAYS: u64 = 90;
+
+fn scan_xcode_artifacts(
     paths: &[String],
-    max_depth: usize,
+    min_dir_size: u64,
+    walk: WalkOptions,
     items: &Arc<Mutex<Vec<CleanableItem>>>,
 ) -> Result<()> {
-    let file_map: Arc<Mutex<HashMap<FileHash, Vec<PathBuf>>>> =
-        Arc::new(Mutex::new(HashMap::new()));
-
-    let mut files_to_hash = Vec::new();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
     for base_path in paths {
-        for entry in WalkDir::new(base_path)
-            .max_depth(max_depth)
-            .follow_links(false)
-            .into_iter()
-            .filter_map(|e| e.ok())
-        {
-            if entry.file_type().is_file() {
-                if let Ok(metadata) = entry.metadata() {
-                    let size = metadata.len();
-                    if size > 1024 * 1024 {
-                        files_to_hash.push((entry.path().to_path_buf(), size));
-                    }
+        let developer = Path::new(base_path).join("Library/Developer");
+        if !developer.exists() {
+            continue;
+        }
+
+        let derived_data = developer.join("Xcode/DerivedData");
+        if derived_data.is_dir() {
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&derived_data, walk)
+            {
+                if size > min_dir_size {
+                    items.lock().unwrap().push(CleanableItem {
+                        path: derived_data.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category: CleanCategory::BuildArtifacts,
+                        risk_level: RiskLevel::Moderate,
+                        description: "Xcode DerivedData".to_string(),
+                        last_modified: get_modified_time(&derived_data),
+                        project: None,
+                        reason: Some("Xcode's build cache location".to_string()),
+                    });
                 }
             }
         }
-    }
 
-    files_to_hash.par_iter().for_each(|(path, size)| {
-        if let Ok(hash) = hash_file(path) {
-            let file_hash = FileHash { hash, size: *size };
-            file_map
-                .lock()
-                .unwrap()
-                .entry(file_hash)
-                .or_default()
-                .push(path.clone());
-        }
-    });
+        // Each simulator device lives in its own UDID-named directory
+        let devices = developer.join("CoreSimulator/Devices");
+        let Ok(entries) = fs::read_dir(&devices) else {
+            continue;
+        };
 
-    let file_map = file_map.lock().unwrap();
-    for (file_hash, paths_list) in file_map.iter() {
-        if paths_list.len() > 1 {
-            for path in paths_list.iter().skip(1) {
-                items.lock().unwrap().push(CleanableItem {
-                    path: path.display().to_string(),
-                    size: file_hash.size,
-                    category: CleanCategory::DuplicateFiles,
-                    risk_level: RiskLevel::Risky,
-                    description: format!(
-                        "Duplicate of {} ({})",
-                        paths_list[0].display(),
-                        format_size(file_hash.size, BINARY)
-                    ),
-                });
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !path.is_dir() {
+                continue;
+            }
+
+            let Some(modified) = get_modified_time(&path.join("data")) else {
+                continue;
+            };
+            let idle_days = now.saturating_sub(modified) / 86400;
+            if idle_days < STALE_SIMULATOR_DAYS {
+                continue;
+            }
+
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&path, walk)
+            {
+                if size > min_dir_size {
+                    items.lock().unwrap().push(CleanableItem {
+                        path: path.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category: CleanCategory::XcodeSimulators,
+                        risk_level: RiskLevel::Moderate,
+                        description: format!(
+                            "Simulator device {} (unused for {} days)",
+                            path.file_name().unwrap_or_default().to_string_lossy(),
+                            idle_days
+                        ),
+                        last_modified: Some(modified),
+                        project: None,
+                        reason: Some(format!(
+                            "simulator data untouched for {}+ days",
+                            STALE_SIMULATOR_DAYS
+                        )),
+                    });
+                }
             }
         }
     }
@@ -447,126 +1741,2337 @@ fn find_duplicates(
     Ok(())
 }
 
-fn hash_file(path: &Path) -> Result<String> {
-    let mut file = fs::File::open(path)?;
-    let mut hasher = Sha256::new();
-    let mut buffer = vec![0; 8192];
+const DOCKER_DATA_DIR: &str = "Library/Containers/com.docker.docker/Data";
 
-    loop {
-        let bytes_read = file.read(&mut buffer)?;
-        if bytes_read == 0 {
-            break;
+fn scan_docker_data(
+    paths: &[String],
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    for base_path in paths {
+        let data_dir = Path::new(base_path).join(DOCKER_DATA_DIR);
+        if !data_dir.is_dir() {
+            continue;
         }
-        hasher.update(&buffer[..bytes_read]);
-    }
-
-    Ok(format!("{:x}", hasher.finalize()))
-}
 
-fn get_dir_size(path: &Path) -> Result<u64> {
-    let mut total = 0;
+        let Ok(
+            measured @ DirSize {
+                bytes: size,
+                estimated,
+                ..
+            },
+        ) = measure_dir(&data_dir, walk)
+        else {
+            continue;
+        };
+        if size <= min_dir_size {
+            continue;
+        }
 
-    for entry in WalkDir::new(path)
-        .follow_links(false)
-        .into_iter()
-        .filter_map(|e| e.ok())
-    {
-        if entry.file_type().is_file() {
-            if let Ok(metadata) = entry.metadata() {
-                total += metadata.len();
+        // Deleting the data directory wipes every image, container and volume,
+        // so point at what Docker itself says can be pruned when we can ask it
+        let description = match docker_reclaimable() {
+            Some(reclaimable) if !reclaimable.is_empty() => {
+                let details: Vec<String> = reclaimable
+                    .iter()
+                    .map(|(kind, bytes)| format!("{} {}", format_size(*bytes, BINARY), kind))
+                    .collect();
+                format!(
+                    "Docker Desktop data (reclaimable via docker prune: {})",
+                    details.join(", ")
+                )
             }
-        }
+            _ => "Docker Desktop data (images, containers and volumes)".to_string(),
+        };
+
+        items.lock().unwrap().push(CleanableItem {
+            path: data_dir.display().to_string(),
+            members: Vec::new(),
+            size,
+            estimated,
+            apparent_size: measured.divergent_apparent(),
+            linked_size: measured.linked_size(),
+            category: CleanCategory::DockerData,
+            risk_level: RiskLevel::Risky,
+            description,
+            last_modified: get_modified_time(&data_dir),
+            project: None,
+            reason: Some(format!(
+                "Docker Desktop's data location ~/{}",
+                DOCKER_DATA_DIR
+            )),
+        });
     }
 
-    Ok(total)
+    Ok(())
 }
 
-fn categorize_cache(path: &Path) -> CleanCategory {
-    let path_str = path.to_string_lossy().to_lowercase();
+/// Ask `docker system df` how much space is reclaimable per resource type.
+/// Returns None when Docker isn't installed or the daemon isn't running.
+fn docker_reclaimable() -> Option<Vec<(String, u64)>> {
+    let output = Command::new("docker")
+        .args(["system", "df", "--format", "{{.Type}}\t{{.Reclaimable}}"])
+        .output()
+        .ok()?;
 
-    if path_str.contains("chrome") || path_str.contains("firefox") || path_str.contains("safari") {
-        CleanCategory::BrowserCache
-    } else if path_str.contains("homebrew") || path_str.contains("brew") {
-        CleanCategory::BrewCache
-    } else if path_str.contains("pip") {
-        CleanCategory::PipCache
-    } else if path_str.contains("cargo") {
-        CleanCategory::CargoCache
-    } else if path_str.contains("npm") || path_str.contains("yarn") || path_str.contains("pnpm") {
-        CleanCategory::AppCache
-    } else if path_str.contains("library/caches") {
-        CleanCategory::SystemCache
-    } else {
-        CleanCategory::AppCache
+    if !output.status.success() {
+        return None;
     }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let reclaimable = stdout
+        .lines()
+        .filter_map(|line| {
+            let (kind, amount) = line.split_once('\t')?;
+            // Reclaimable is reported like "10.1GB (82%)"
+            let bytes = parse_docker_size(amount.split_whitespace().next()?)?;
+            (bytes > 0).then(|| (kind.to_lowercase(), bytes))
+        })
+        .collect();
+
+    Some(reclaimable)
 }
 
-pub fn display_results(results: &ScanResults) {
-    println!("\n{}", "=== Scan Results ===".green().bold());
-    println!(
-        "Total cleanable space: {}\n",
-        format_size(results.total_size, BINARY).bold()
-    );
+/// Parse Docker's human-readable sizes ("1.5GB", "512kB", "0B") into bytes
+fn parse_docker_size(s: &str) -> Option<u64> {
+    let split = s.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier = match unit.to_ascii_uppercase().as_str() {
+        "B" => 1.0,
+        "KB" => 1e3,
+        "MB" => 1e6,
+        "GB" => 1e9,
+        "TB" => 1e12,
+        _ => return None,
+    };
+
+    Some((number * multiplier) as u64)
+}
 
-    // Group by risk level
-    let mut by_risk: HashMap<RiskLevel, Vec<&CleanableItem>> = HashMap::new();
-    for item in &results.items {
-        by_risk.entry(item.risk_level).or_default().push(item);
-    }
+/// Folders where Mail keeps attachments it has opened, relative to the home folder
+const MAIL_DOWNLOAD_DIRS: &[&str] = &[
+    "Library/Containers/com.apple.mail/Data/Library/Mail Downloads",
+    "Library/Mail Downloads",
+];
 
-    // Display by risk level
-    for risk in [RiskLevel::Safe, RiskLevel::Moderate, RiskLevel::Risky] {
-        if let Some(items) = by_risk.get(&risk) {
-            let total: u64 = items.iter().map(|i| i.size).sum();
+/// Report Mail's downloaded attachments and the per-message `Attachments`
+/// folders under `~/Library/Mail`. Mail fetches these again from the server on
+/// demand; the message store and `MailData` databases are never reported.
+fn scan_mail_attachments(
+    paths: &[String],
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    for base_path in paths {
+        let mail = Path::new(base_path).join("Library/Mail");
+        if !mail.exists() {
+            continue;
+        }
 
-            let risk_color = match risk {
-                RiskLevel::Safe => "green",
-                RiskLevel::Moderate => "yellow",
-                RiskLevel::Risky => "red",
+        let mut found: Vec<(PathBuf, String)> = MAIL_DOWNLOAD_DIRS
+            .iter()
+            .map(|dir| Path::new(base_path).join(dir))
+            .filter(|dir| dir.is_dir())
+            .map(|dir| (dir, "Mail downloaded attachments".to_string()))
+            .collect();
+
+        for entry in walk_dir(&mail, usize::MAX, walk.follow_links, true, &[]) {
+            if !entry.file_type().is_dir() || entry.file_name() != "Attachments" {
+                continue;
+            }
+            let path = entry.path();
+            if path.components().any(|c| c.as_os_str() == "MailData") {
+                continue;
+            }
+
+            // Name the mailbox the attachments belong to
+            let mailbox = path
+                .ancestors()
+                .filter_map(|p| p.file_name())
+                .map(|name| name.to_string_lossy())
+                .find(|name| name.ends_with(".mbox"))
+                .map(|name| name.trim_end_matches(".mbox").to_string());
+            let description = match mailbox {
+                Some(mailbox) => format!("Mail attachment cache ({})", mailbox),
+                None => "Mail attachment cache".to_string(),
             };
+            found.push((path.to_path_buf(), description));
+        }
 
-            println!(
-                "{} ({}, {} items)",
-                format!("{:?} Risk", risk).color(risk_color).bold(),
-                format_size(total, BINARY).bold(),
-                items.len()
-            );
+        for (path, description) in found {
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&path, walk)
+            {
+                if size > min_dir_size {
+                    items.lock().unwrap().push(CleanableItem {
+                        path: path.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category: CleanCategory::AppCache,
+                        risk_level: RiskLevel::Moderate,
+                        description,
+                        last_modified: get_modified_time(&path),
+                        project: None,
+                        reason: Some(if path.ends_with("Attachments") {
+                            "`Attachments` folder inside ~/Library/Mail".to_string()
+                        } else {
+                            "Mail's downloaded attachments folder".to_string()
+                        }),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Quick Look's thumbnail cache, rebuilt whenever a preview is needed again
+const QUICKLOOK_CACHE_DIR: &str = "Library/Caches/com.apple.QuickLook.thumbnailcache";
+
+/// Report every `.DS_Store` under each scan path as one item, since they're
+/// tiny on their own, plus Quick Look's thumbnail cache
+fn scan_clutter(
+    paths: &[String],
+    max_depth: usize,
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    // Whatever is already reported gets deleted whole, .DS_Store included,
+    // and subtrees an incremental scan carries forward aren't searched again
+    let mut skip: Vec<PathBuf> = items
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|item| PathBuf::from(&item.path))
+        .chain(walk.unchanged.iter().cloned())
+        .collect();
+    skip.sort();
 
-            // Group by category within risk level
-            let mut by_category: HashMap<CleanCategory, Vec<&CleanableItem>> = HashMap::new();
-            for item in items {
-                by_category.entry(item.category).or_default().push(item);
+    for base_path in paths {
+        let mut members = Vec::new();
+        let mut measured = DirSize::default();
+        let mut last_modified = None;
+
+        for entry in walk_dir(base_path, max_depth, walk.follow_links, false, &skip) {
+            if !entry.file_type().is_file() || entry.file_name() != ".DS_Store" {
+                continue;
             }
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
 
-            for (category, cat_items) in by_category {
-                let cat_total: u64 = cat_items.iter().map(|i| i.size).sum();
-                println!(
-                    "  {} - {} ({} items)",
-                    category,
-                    format_size(cat_total, BINARY),
-                    cat_items.len()
-                );
+            measured.add(DirSize::of_file(&metadata, walk));
+            last_modified = last_modified.max(modified_secs(&metadata));
+            members.push(entry.path().display().to_string());
+        }
 
-                // Show top 3 items in this category
-                let mut sorted_items = cat_items.clone();
-                sorted_items.sort_by(|a, b| b.size.cmp(&a.size));
-                for item in sorted_items.iter().take(3) {
-                    println!(
-                        "    {} - {}",
-                        format_size(item.size, BINARY),
-                        item.path.dimmed()
-                    );
-                }
-                if cat_items.len() > 3 {
-                    println!("    ... and {} more", cat_items.len() - 3);
-                }
+        if !members.is_empty() && measured.bytes > min_dir_size {
+            items.lock().unwrap().push(CleanableItem {
+                path: Path::new(base_path)
+                    .join("**/.DS_Store")
+                    .display()
+                    .to_string(),
+                size: measured.bytes,
+                estimated: false,
+                apparent_size: measured.divergent_apparent(),
+                linked_size: None,
+                category: CleanCategory::TempFiles,
+                risk_level: RiskLevel::Safe,
+                description: format!("{} .DS_Store files", members.len()),
+                last_modified,
+                project: None,
+                reason: Some(
+                    "Finder view settings, recreated when a folder is next opened".to_string(),
+                ),
+                members,
+            });
+        }
+
+        let thumbnails = Path::new(base_path).join(QUICKLOOK_CACHE_DIR);
+        if let Ok(measured) = measure_dir(&thumbnails, walk) {
+            if measured.bytes > min_dir_size {
+                items.lock().unwrap().push(CleanableItem {
+                    path: thumbnails.display().to_string(),
+                    members: Vec::new(),
+                    size: measured.bytes,
+                    estimated: measured.estimated,
+                    apparent_size: measured.divergent_apparent(),
+                    linked_size: measured.linked_size(),
+                    category: CleanCategory::TempFiles,
+                    risk_level: RiskLevel::Safe,
+                    description: "Quick Look thumbnail cache".to_string(),
+                    last_modified: get_modified_time(&thumbnails),
+                    project: None,
+                    reason: Some(format!(
+                        "Quick Look's thumbnail cache in ~/{}",
+                        QUICKLOOK_CACHE_DIR
+                    )),
+                });
             }
-            println!();
         }
     }
 
-    println!(
-        "\n{}",
-        "Run 'cleanser clean --risk <level>' to clean files".cyan()
-    );
+    Ok(())
+}
+
+/// Where Finder (and iTunes before it) keeps iPhone and iPad backups. The
+/// generic scanners skip `Library/Application Support` entirely.
+const DEVICE_BACKUP_DIR: &str = "Library/Application Support/MobileSync/Backup";
+
+fn scan_device_backups(
+    paths: &[String],
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    for base_path in paths {
+        let backup_dir = Path::new(base_path).join(DEVICE_BACKUP_DIR);
+        let Ok(entries) = fs::read_dir(&backup_dir) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+
+            let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&path, walk)
+            else {
+                continue;
+            };
+            if size == 0 {
+                continue;
+            }
+
+            let last_modified = get_modified_time(&path);
+            let device = backup_device_name(&path).unwrap_or_else(|| "unknown device".to_string());
+            let description = match last_modified {
+                Some(modified) => format!(
+                    "iOS device backup of {} (last modified {})",
+                    device,
+                    format_age(modified)
+                ),
+                None => format!("iOS device backup of {}", device),
+            };
+
+            // Deleting a backup loses it for good, so it's always a review item
+            items.lock().unwrap().push(CleanableItem {
+                path: path.display().to_string(),
+                members: Vec::new(),
+                size,
+                estimated,
+                apparent_size: measured.divergent_apparent(),
+                linked_size: measured.linked_size(),
+                category: CleanCategory::DeviceBackups,
+                risk_level: RiskLevel::Risky,
+                description,
+                last_modified,
+                project: None,
+                reason: Some(format!("device backup folder in ~/{}", DEVICE_BACKUP_DIR)),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// The device name recorded in a backup's `Info.plist`, if it's readable
+fn backup_device_name(backup: &Path) -> Option<String> {
+    let info = fs::read_to_string(backup.join("Info.plist")).ok()?;
+    let re = Regex::new(r"<key>Device Name</key>\s*<string>([^<]*)</string>").ok()?;
+    re.captures(&info).map(|caps| caps[1].to_string())
+}
+
+/// Downloads untouched for this many days are reported as old
+const OLD_DOWNLOAD_DAYS: u64 = 90;
+
+fn scan_trash_and_downloads(
+    paths: &[String],
+    min_dir_size: u64,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    for base_path in paths {
+        let trash = Path::new(base_path).join(".Trash");
+        if trash.is_dir() {
+            if let Ok(
+                measured @ DirSize {
+                    bytes: size,
+                    estimated,
+                    ..
+                },
+            ) = measure_dir(&trash, walk)
+            {
+                if size > min_dir_size {
+                    items.lock().unwrap().push(CleanableItem {
+                        path: trash.display().to_string(),
+                        members: Vec::new(),
+                        size,
+                        estimated,
+                        apparent_size: measured.divergent_apparent(),
+                        linked_size: measured.linked_size(),
+                        category: CleanCategory::Trash,
+                        risk_level: RiskLevel::Safe,
+                        description: "Trash (will be emptied)".to_string(),
+                        last_modified: get_modified_time(&trash),
+                        project: None,
+                        reason: Some("the Trash folder".to_string()),
+                    });
+                }
+            }
+        }
+
+        let Ok(entries) = fs::read_dir(Path::new(base_path).join("Downloads")) else {
+            continue;
+        };
+
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+
+            let Some(idle_days) = idle_days(&path) else {
+                continue;
+            };
+            if idle_days < OLD_DOWNLOAD_DAYS {
+                continue;
+            }
+
+            let measured = match entry.file_type() {
+                Ok(t) if t.is_dir() => measure_dir(&path, walk).unwrap_or_default(),
+                Ok(_) => entry
+                    .metadata()
+                    .map(|m| DirSize::of_file(&m, walk))
+                    .unwrap_or_default(),
+                Err(_) => continue,
+            };
+
+            if measured.bytes > min_dir_size {
+                items.lock().unwrap().push(CleanableItem {
+                    path: path.display().to_string(),
+                    members: Vec::new(),
+                    size: measured.bytes,
+                    estimated: measured.estimated,
+                    apparent_size: measured.divergent_apparent(),
+                    linked_size: measured.linked_size(),
+                    category: CleanCategory::OldDownloads,
+                    risk_level: RiskLevel::Moderate,
+                    description: format!("Download untouched for {} days", idle_days),
+                    last_modified: get_modified_time(&path),
+                    project: None,
+                    reason: Some(format!(
+                        "in ~/Downloads and untouched for {}+ days",
+                        OLD_DOWNLOAD_DAYS
+                    )),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Home directories holding the user's own files
+const USER_DATA_DIRS: &[&str] = &["Documents", "Desktop", "Pictures", "Movies", "Music"];
+
+/// `--ext`/`--exclude-ext` for large files, as lowercase `.ext` suffixes so
+/// multi-dot extensions like `.tar.gz` match too
+struct ExtensionFilter {
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl ExtensionFilter {
+    fn new(include: &[String], exclude: &[String]) -> Self {
+        let suffixes = |extensions: &[String]| {
+            extensions
+                .iter()
+                .map(|ext| format!(".{}", ext.trim_start_matches('.').to_lowercase()))
+                .collect()
+        };
+        ExtensionFilter {
+            include: suffixes(include),
+            exclude: suffixes(exclude),
+        }
+    }
+
+    /// Whether `--ext` names this file's extension (not merely allows it)
+    fn includes(&self, file_name: &str) -> bool {
+        let name = file_name.to_lowercase();
+        self.include.iter().any(|ext| name.ends_with(ext))
+            && !self.exclude.iter().any(|ext| name.ends_with(ext))
+    }
+
+    fn allows(&self, file_name: &str) -> bool {
+        let name = file_name.to_lowercase();
+        (self.include.is_empty() || self.include.iter().any(|ext| name.ends_with(ext)))
+            && !self.exclude.iter().any(|ext| name.ends_with(ext))
+    }
+}
+
+/// Library bundles of media apps: (extension, app)
+const MEDIA_LIBRARIES: &[(&str, &str)] = &[
+    ("photoslibrary", "Photos"),
+    ("fcpbundle", "Final Cut Pro"),
+    ("imovielibrary", "iMovie"),
+    ("aplibrary", "Aperture"),
+];
+
+/// The app owning a media library bundle, if `path` is one
+fn media_library_app(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?;
+    MEDIA_LIBRARIES
+        .iter()
+        .find(|(library, _)| ext.eq_ignore_ascii_case(library))
+        .map(|(_, app)| *app)
+}
+
+fn scan_large_files(
+    paths: &[String],
+    max_depth: usize,
+    min_size_mb: u64,
+    extensions: &ExtensionFilter,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+) -> Result<()> {
+    let min_size = min_size_mb * 1024 * 1024;
+
+    let skip_dirs = [
+        "Library/Application Support",
+        "Library/Mobile Documents",
+        "Applications",
+        "/System",
+        "/Library",
+        "Library/Mail",
+    ];
+
+    // Large files here are someone's documents and photos rather than clutter
+    let user_data_dirs: Vec<PathBuf> = match std::env::var("HOME") {
+        Ok(home) => USER_DATA_DIRS
+            .iter()
+            .map(|dir| Path::new(&home).join(dir))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    for base_path in paths {
+        for entry in walk_dir(
+            base_path,
+            max_depth,
+            walk.follow_links,
+            false,
+            walk.unchanged,
+        ) {
+            let path = entry.path();
+            let path_str = path.to_string_lossy();
+
+            if skip_dirs.iter().any(|skip| path_str.contains(skip)) {
+                continue;
+            }
+
+            // A media library's internals are managed by its app, so only
+            // the library as a whole is reported
+            if let Some(app) = media_library_app(path).filter(|_| entry.file_type().is_dir()) {
+                if let Ok(measured) = measure_dir(path, walk) {
+                    if measured.bytes > min_size {
+                        items.lock().unwrap().push(CleanableItem {
+                            path: path.display().to_string(),
+                            members: Vec::new(),
+                            size: measured.bytes,
+                            estimated: measured.estimated,
+                            apparent_size: measured.divergent_apparent(),
+                            linked_size: measured.linked_size(),
+                            category: CleanCategory::MediaLibraries,
+                            risk_level: RiskLevel::Manual,
+                            description: format!("{} library", app),
+                            last_modified: get_modified_time(path),
+                            project: None,
+                            reason: Some(format!(
+                                "`.{}` library bundle, reported whole and never cleaned in bulk",
+                                path.extension().unwrap_or_default().to_string_lossy()
+                            )),
+                        });
+                    }
+                }
+                continue;
+            }
+
+            let name = entry.file_name().to_string_lossy();
+            if name.starts_with('.') && name != ".cache" {
+                continue;
+            }
+
+            // Any other bundle counts as one large file, but only when --ext
+            // asks for its kind (e.g. `--ext app`)
+            let (measured, last_modified) = if entry.file_type().is_file()
+                && extensions.allows(&name)
+            {
+                match entry.metadata() {
+                    Ok(metadata) => (DirSize::of_file(&metadata, walk), modified_secs(&metadata)),
+                    Err(_) => continue,
+                }
+            } else if entry.file_type().is_dir() && is_bundle(path) && extensions.includes(&name) {
+                match measure_dir(path, walk) {
+                    Ok(measured) => (measured, get_modified_time(path)),
+                    Err(_) => continue,
+                }
+            } else {
+                continue;
+            };
+
+            let size = measured.bytes;
+            if size > min_size {
+                let personal = user_data_dirs.iter().find(|dir| path.starts_with(dir));
+                let mut reason = format!("file of {} MB or more (--min-size)", min_size_mb);
+                if let Some(dir) = personal {
+                    reason.push_str(&format!(", inside {}", dir.display()));
+                }
+                items.lock().unwrap().push(CleanableItem {
+                    path: path.display().to_string(),
+                    members: Vec::new(),
+                    size,
+                    estimated: measured.estimated,
+                    apparent_size: measured.divergent_apparent(),
+                    linked_size: measured.linked_size(),
+                    category: CleanCategory::LargeFiles,
+                    risk_level: if personal.is_some() {
+                        RiskLevel::Manual
+                    } else {
+                        RiskLevel::Risky
+                    },
+                    description: format!("Large file ({})", format_size(size, BINARY)),
+                    last_modified,
+                    project: None,
+                    reason: Some(reason),
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// How often duplicate hashing reports its progress
+const HASH_PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+fn find_duplicates(
+    paths: &[String],
+    max_depth: usize,
+    min_size: u64,
+    hash_threads: Option<usize>,
+    walk: WalkOptions,
+    items: &Arc<Mutex<Vec<CleanableItem>>>,
+    progress: &Progress,
+) -> Result<Vec<DuplicateGroup>> {
+    let file_map: Arc<Mutex<HashMap<FileHash, Vec<PathBuf>>>> =
+        Arc::new(Mutex::new(HashMap::new()));
+
+    let mut files_to_hash = Vec::new();
+    // Hard links share one inode, so deleting a "copy" frees nothing: only
+    // the first link seen is hashed
+    let mut seen_inodes: HashSet<(u64, u64)> = HashSet::new();
+
+    for base_path in paths {
+        for entry in walk_dir(
+            base_path,
+            max_depth,
+            walk.follow_links,
+            false,
+            walk.unchanged,
+        ) {
+            if entry.file_type().is_file() {
+                if let Ok(metadata) = entry.metadata() {
+                    let size = metadata.len();
+                    if size > min_size && seen_inodes.insert((metadata.dev(), metadata.ino())) {
+                        files_to_hash.push((entry.path().to_path_buf(), size));
+                    }
+                }
+            }
+        }
+    }
+
+    // Parallel reads help on SSDs but make spinning disks and network
+    // volumes seek back and forth, which is slower than reading in turn
+    let threads = hash_threads.unwrap_or_else(|| {
+        if paths.iter().any(|p| is_network_volume(Path::new(p))) {
+            1
+        } else {
+            rayon::current_num_threads().min(8)
+        }
+    });
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.max(1))
+        .build()
+        .context("Failed to start hashing threads")?;
+
+    // Hashing a media library can take minutes, so the hashing runs on its
+    // own thread while this one reports how far it has got. Files that fail
+    // to hash still count as done.
+    let files_done = AtomicUsize::new(0);
+    let bytes_done = AtomicU64::new(0);
+    let bytes_total = files_to_hash.iter().map(|(_, size)| size).sum();
+    let report_hashing = || {
+        if files_to_hash.is_empty() {
+            return;
+        }
+        progress.emit(ScanEvent::Hashing {
+            files_done: files_done.load(Ordering::Relaxed),
+            files_total: files_to_hash.len(),
+            bytes_done: bytes_done.load(Ordering::Relaxed),
+            bytes_total,
+        })
+    };
+
+    std::thread::scope(|scope| {
+        let hashing = scope.spawn(|| {
+            pool.install(|| {
+                files_to_hash.par_iter().for_each(|(path, size)| {
+                    if let Ok(hash) = hash_file(path) {
+                        walk.stats.bytes_hashed.fetch_add(*size, Ordering::Relaxed);
+                        let file_hash = FileHash { hash, size: *size };
+                        file_map
+                            .lock()
+                            .unwrap()
+                            .entry(file_hash)
+                            .or_default()
+                            .push(path.clone());
+                    }
+                    files_done.fetch_add(1, Ordering::Relaxed);
+                    bytes_done.fetch_add(*size, Ordering::Relaxed);
+                })
+            })
+        });
+
+        while !hashing.is_finished() {
+            report_hashing();
+            std::thread::sleep(HASH_PROGRESS_INTERVAL);
+        }
+        if let Err(panic) = hashing.join() {
+            std::panic::resume_unwind(panic);
+        }
+    });
+    report_hashing();
+
+    let mut groups = Vec::new();
+    let file_map = file_map.lock().unwrap();
+    for (file_hash, paths_list) in file_map.iter() {
+        if paths_list.len() > 1 {
+            let mut group = DuplicateGroup {
+                hash: file_hash.hash.clone(),
+                size: file_hash.size,
+                paths: vec![paths_list[0].display().to_string()],
+            };
+
+            for path in paths_list.iter().skip(1) {
+                let mut measured = fs::metadata(path)
+                    .map(|metadata| DirSize::of_file(&metadata, walk))
+                    .unwrap_or(DirSize {
+                        bytes: file_hash.size,
+                        apparent_bytes: file_hash.size,
+                        linked_bytes: 0,
+                        estimated: false,
+                    });
+                let mut description = format!(
+                    "Duplicate of {} ({})",
+                    paths_list[0].display(),
+                    format_size(file_hash.size, BINARY)
+                );
+
+                // An APFS clone shares its blocks with the original until
+                // either is modified; only the private blocks come back
+                match private_size(path) {
+                    Some(0) => continue,
+                    Some(private) if private < measured.bytes => {
+                        measured.bytes = private;
+                        description.push_str(&format!(
+                            ", APFS clone: frees only {}",
+                            format_size(private, BINARY)
+                        ));
+                    }
+                    _ => {}
+                }
+
+                items.lock().unwrap().push(CleanableItem {
+                    path: path.display().to_string(),
+                    members: Vec::new(),
+                    size: measured.bytes,
+                    estimated: false,
+                    apparent_size: measured.divergent_apparent(),
+                    linked_size: measured.linked_size(),
+                    category: CleanCategory::DuplicateFiles,
+                    risk_level: RiskLevel::Risky,
+                    description,
+                    last_modified: get_modified_time(path),
+                    project: None,
+                    reason: Some(format!(
+                        "same size and XXH3-128 hash as {}",
+                        paths_list[0].display()
+                    )),
+                });
+                group.paths.push(path.display().to_string());
+            }
+
+            if group.paths.len() > 1 {
+                groups.push(group);
+            }
+        }
+    }
+
+    // Largest savings first
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.paths.cmp(&b.paths)));
+
+    Ok(groups)
+}
+
+/// Bytes of `path` not shared with any APFS clone, i.e. what deleting it
+/// would actually free. `None` when the filesystem can't tell us.
+#[cfg(target_os = "macos")]
+fn private_size(path: &Path) -> Option<u64> {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    #[repr(C, packed(4))]
+    struct PrivateSizeBuf {
+        length: u32,
+        private_size: libc::off_t,
+    }
+
+    let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+    // SAFETY: attrlist is plain data, all-zero is a valid empty request
+    let mut request: libc::attrlist = unsafe { std::mem::zeroed() };
+    request.bitmapcount = libc::ATTR_BIT_MAP_COUNT;
+    request.forkattr = libc::ATTR_CMNEXT_PRIVATESIZE;
+    let mut buf = PrivateSizeBuf {
+        length: 0,
+        private_size: 0,
+    };
+
+    // SAFETY: both pointers reference live locals and the size passed is
+    // that of the output buffer
+    let rc = unsafe {
+        libc::getattrlist(
+            c_path.as_ptr(),
+            &mut request as *mut libc::attrlist as *mut libc::c_void,
+            &mut buf as *mut PrivateSizeBuf as *mut libc::c_void,
+            std::mem::size_of::<PrivateSizeBuf>(),
+            libc::FSOPT_ATTR_CMN_EXTENDED | libc::FSOPT_NOFOLLOW,
+        )
+    };
+    if rc != 0 || (buf.length as usize) < std::mem::size_of::<PrivateSizeBuf>() {
+        return None;
+    }
+
+    u64::try_from(buf.private_size).ok()
+}
+
+#[cfg(not(target_os = "macos"))]
+fn private_size(_path: &Path) -> Option<u64> {
+    None
+}
+
+/// Whether `path` lives on a network file system (SMB, NFS, AFP, WebDAV...)
+#[cfg(target_os = "macos")]
+fn is_network_volume(path: &Path) -> bool {
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    let Ok(c_path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: statfs is plain data and is only read after a successful call
+    let mut stats: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(c_path.as_ptr(), &mut stats) } != 0 {
+        return false;
+    }
+
+    stats.f_flags & libc::MNT_LOCAL as u32 == 0
+}
+
+#[cfg(not(target_os = "macos"))]
+fn is_network_volume(_path: &Path) -> bool {
+    false
+}
+
+/// 128-bit XXH3 of a file's contents. Not cryptographic, but collisions
+/// between files on one disk are vanishingly unlikely and it's several times
+/// faster than SHA-256.
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Xxh3::new();
+    let mut buffer = vec![0; 64 * 1024];
+
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
+
+    Ok(format!("{:032x}", hasher.digest128()))
+}
+
+/// Directory extensions macOS treats as a single opaque item: apps, plugins,
+/// frameworks and app libraries
+const BUNDLE_EXTENSIONS: &[&str] = &[
+    "app",
+    "appex",
+    "bundle",
+    "framework",
+    "kext",
+    "plugin",
+    "xpc",
+    "xcarchive",
+    "photoslibrary",
+    "fcpbundle",
+    "imovielibrary",
+    "aplibrary",
+];
+
+fn is_bundle(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| {
+        BUNDLE_EXTENSIONS
+            .iter()
+            .any(|bundle| ext.eq_ignore_ascii_case(bundle))
+    })
+}
+
+/// Walk a directory tree, skipping unreadable entries and the `skip` subtrees
+/// (sorted, so each entry is looked up with a binary search).
+/// When following symlinks, each directory is visited at most once (tracked by
+/// device and inode) so symlink loops and aliased directories can't hang or
+/// double-count the walk. Unless `into_bundles` is set, bundles are yielded
+/// but not entered, apart from `path` itself.
+fn walk_dir<'a>(
+    path: impl AsRef<Path>,
+    max_depth: usize,
+    follow_links: bool,
+    into_bundles: bool,
+    skip: &'a [PathBuf],
+) -> impl Iterator<Item = DirEntry> + 'a {
+    debug_assert!(skip.windows(2).all(|pair| pair[0] <= pair[1]));
+    let mut visited: HashSet<(u64, u64)> = HashSet::new();
+
+    WalkDir::new(path)
+        .max_depth(max_depth)
+        .follow_links(follow_links)
+        .into_iter()
+        .filter_entry(move |entry| {
+            if skip
+                .binary_search_by(|dir| dir.as_path().cmp(entry.path()))
+                .is_ok()
+            {
+                return false;
+            }
+            // Dropping a bundle's children keeps the walk out of it
+            if !into_bundles && entry.depth() > 1 && entry.path().parent().is_some_and(is_bundle) {
+                return false;
+            }
+            if !follow_links || !entry.file_type().is_dir() {
+                return true;
+            }
+            match entry.metadata() {
+                Ok(metadata) => visited.insert((metadata.dev(), metadata.ino())),
+                Err(_) => true,
+            }
+        })
+        .filter_map(|e| e.ok())
+}
+
+/// Stop an `--estimate` walk after visiting this many entries
+const ESTIMATE_ENTRY_LIMIT: usize = 10_000;
+
+/// How directory trees are walked and measured
+#[derive(Debug, Clone, Copy)]
+struct WalkOptions<'a> {
+    follow_links: bool,
+    estimate: bool,
+    /// Measure apparent file lengths instead of allocated disk blocks
+    apparent: bool,
+    /// Also measure what symlinks inside a directory point to
+    dereference: bool,
+    /// Subtrees an incremental scan carries forward instead of searching again
+    unchanged: &'a [PathBuf],
+    stats: &'a ScanStats,
+}
+
+/// A measured size, possibly extrapolated from a partial walk. `bytes` is
+/// on-disk usage unless apparent sizes were requested.
+#[derive(Debug, Clone, Copy, Default)]
+struct DirSize {
+    bytes: u64,
+    /// Sum of apparent file lengths
+    apparent_bytes: u64,
+    /// What symlinks inside point to, measured like `bytes` (only with
+    /// `--dereference-size`)
+    linked_bytes: u64,
+    estimated: bool,
+}
+
+impl DirSize {
+    fn of_file(metadata: &fs::Metadata, walk: WalkOptions) -> DirSize {
+        DirSize {
+            bytes: if walk.apparent {
+                metadata.len()
+            } else {
+                metadata.blocks() * 512
+            },
+            apparent_bytes: metadata.len(),
+            linked_bytes: 0,
+            estimated: false,
+        }
+    }
+
+    fn add(&mut self, other: DirSize) {
+        self.bytes += other.bytes;
+        self.apparent_bytes += other.apparent_bytes;
+        self.linked_bytes += other.linked_bytes;
+    }
+
+    /// The size including symlink targets, when any were measured
+    fn linked_size(&self) -> Option<u64> {
+        (self.linked_bytes > 0).then_some(self.bytes + self.linked_bytes)
+    }
+
+    /// The apparent size, when it differs from `bytes` by more than 10%
+    /// (compressed, sparse or cloned files)
+    fn divergent_apparent(&self) -> Option<u64> {
+        let difference = self.bytes.abs_diff(self.apparent_bytes);
+        (difference > self.bytes / 10 && difference > 0).then_some(self.apparent_bytes)
+    }
+}
+
+fn measure_dir(path: &Path, walk: WalkOptions) -> Result<DirSize> {
+    let started = Instant::now();
+    let measured = if walk.estimate {
+        estimate_dir_size(path, walk)
+    } else {
+        get_dir_size(path, walk)
+    };
+    walk.stats.record_sizing(started);
+    measured
+}
+
+/// Size of a file, or of a whole directory, without following a symlink at
+/// `path` itself. Anything unreadable counts as empty.
+fn measure_path(path: &Path, walk: WalkOptions) -> DirSize {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_file() => DirSize::of_file(&metadata, walk),
+        Ok(metadata) if metadata.is_dir() => get_dir_size(path, walk).unwrap_or_default(),
+        _ => DirSize::default(),
+    }
+}
+
+/// Sum file sizes until `ESTIMATE_ENTRY_LIMIT` entries have been visited, then
+/// extrapolate from how many of the top-level children the walk got through
+fn estimate_dir_size(path: &Path, walk: WalkOptions) -> Result<DirSize> {
+    let top_level = fs::read_dir(path)?.count().max(1);
+
+    let mut total = DirSize::default();
+    let mut top_level_seen = 0;
+    let mut targets = HashSet::new();
+
+    for (visited, entry) in walk_dir(path, usize::MAX, walk.follow_links, true, &[]).enumerate() {
+        if visited >= ESTIMATE_ENTRY_LIMIT {
+            // The current top-level child is only partially walked
+            let fraction = ((top_level_seen as f64 - 0.5).max(0.5) / top_level as f64).min(1.0);
+            return Ok(DirSize {
+                bytes: (total.bytes as f64 / fraction) as u64,
+                apparent_bytes: (total.apparent_bytes as f64 / fraction) as u64,
+                linked_bytes: (total.linked_bytes as f64 / fraction) as u64,
+                estimated: true,
+            });
+        }
+
+        if entry.depth() == 1 {
+            top_level_seen += 1;
+        }
+
+        if entry.file_type().is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                total.add(DirSize::of_file(&metadata, walk));
+            }
+        } else if entry.path_is_symlink() && walk.dereference {
+            total.linked_bytes += symlink_target_size(entry.path(), walk, &mut targets);
+        }
+    }
+
+    Ok(total)
+}
+
+fn get_dir_size(path: &Path, walk: WalkOptions) -> Result<DirSize> {
+    let mut total = DirSize::default();
+    let mut targets = HashSet::new();
+
+    for entry in walk_dir(path, usize::MAX, walk.follow_links, true, &[]) {
+        if entry.file_type().is_file() {
+            if let Ok(metadata) = entry.metadata() {
+                total.add(DirSize::of_file(&metadata, walk));
+            }
+        } else if entry.path_is_symlink() && walk.dereference {
+            total.linked_bytes += symlink_target_size(entry.path(), walk, &mut targets);
+        }
+    }
+
+    Ok(total)
+}
+
+/// Size of what a symlink points to, a whole tree for a directory, without
+/// following further links. Targets already in `seen` (by device and inode)
+/// count once.
+fn symlink_target_size(link: &Path, walk: WalkOptions, seen: &mut HashSet<(u64, u64)>) -> u64 {
+    let Ok(metadata) = fs::metadata(link) else {
+        return 0;
+    };
+    if !seen.insert((metadata.dev(), metadata.ino())) {
+        return 0;
+    }
+    if !metadata.is_dir() {
+        return DirSize::of_file(&metadata, walk).bytes;
+    }
+
+    walk_dir(link, usize::MAX, false, true, &[])
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .filter(|metadata| seen.insert((metadata.dev(), metadata.ino())))
+        .map(|metadata| DirSize::of_file(&metadata, walk).bytes)
+        .sum()
+}
+
+/// Get the modification time of a path in seconds since the Unix epoch
+fn get_modified_time(path: &Path) -> Option<u64> {
+    fs::symlink_metadata(path)
+        .ok()
+        .and_then(|metadata| modified_secs(&metadata))
+}
+
+fn modified_secs(metadata: &fs::Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+/// Format an epoch timestamp as a coarse "N days ago" string
+fn format_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(timestamp);
+    let days = now.saturating_sub(timestamp) / 86400;
+
+    match days {
+        0 => "today".to_string(),
+        1 => "1 day ago".to_string(),
+        d => format!("{} days ago", d),
+    }
+}
+
+/// Format an item's size, marking extrapolated sizes with a leading `~`
+pub fn format_item_size(item: &CleanableItem) -> String {
+    let mut size = format_total(item.size, item.estimated);
+    if let Some(apparent) = item.apparent_size {
+        size.push_str(&format!(
+            " (apparent {})",
+            format_total(apparent, item.estimated)
+        ));
+    }
+    if let Some(linked) = item.linked_size {
+        size.push_str(&format!(
+            " ({} with symlink targets)",
+            format_total(linked, item.estimated)
+        ));
+    }
+    size
+}
+
+fn format_total(size: u64, estimated: bool) -> String {
+    let size = format_size(size, BINARY);
+    if estimated {
+        format!("~{}", size)
+    } else {
+        size
+    }
+}
+
+/// Sort items in place according to the requested order
+fn sort_items(items: &mut [&CleanableItem], order: SortOrder) {
+    match order {
+        SortOrder::Size => items.sort_by_key(|item| std::cmp::Reverse(item.size)),
+        // Oldest first; items without a timestamp go last
+        SortOrder::Age => items.sort_by_key(|item| item.last_modified.unwrap_or(u64::MAX)),
+    }
+}
+
+fn categorize_cache(path: &Path) -> CleanCategory {
+    let path_str = path.to_string_lossy().to_lowercase();
+
+    if path_str.contains("chrome") || path_str.contains("firefox") || path_str.contains("safari") {
+        CleanCategory::BrowserCache
+    } else if path_str.contains("homebrew") || path_str.contains("brew") {
+        CleanCategory::BrewCache
+    } else if path_str.contains("pip") {
+        CleanCategory::PipCache
+    } else if path_str.contains("cargo") {
+        CleanCategory::CargoCache
+    } else if path_str.contains("npm") || path_str.contains("yarn") || path_str.contains("pnpm") {
+        CleanCategory::AppCache
+    } else if path_str.contains("library/caches") {
+        CleanCategory::SystemCache
+    } else {
+        CleanCategory::AppCache
+    }
+}
+
+/// Write the human-readable scan report. Categories totalling less than
+/// `collapse_below` bytes are summed up on a single "Other" line.
+pub fn display_results(
+    out: &mut dyn Write,
+    results: &ScanResults,
+    sort: SortOrder,
+    collapse_below: u64,
+    explain: bool,
+    width: usize,
+    group_by: GroupBy,
+) -> io::Result<()> {
+    writeln!(out, "\n{}", "=== Scan Results ===".green().bold())?;
+    if !results.scanned_paths.is_empty() {
+        writeln!(
+            out,
+            "Scanned: {} ({} scan)",
+            results.scanned_paths.join(", "),
+            results.scan_speed
+        )?;
+    }
+    if results.incremental {
+        writeln!(
+            out,
+            "{}",
+            "Incremental scan: items in directories unchanged since the last scan were carried forward"
+                .yellow()
+        )?;
+    }
+    let any_estimated = results.items.iter().any(|i| i.estimated);
+    writeln!(
+        out,
+        "Total cleanable space: {}\n",
+        format_total(results.total_size, any_estimated).bold()
+    )?;
+
+    match group_by {
+        GroupBy::Risk => display_by_risk(out, results, sort, collapse_below, explain, width)?,
+        GroupBy::Category => {
+            let groups = group_by_category(&results.items)
+                .into_iter()
+                .map(|(category, items)| (category.to_string(), items))
+                .collect();
+            display_groups(out, groups, sort, collapse_below, explain, width)?
+        }
+        GroupBy::Path => {
+            let groups = results
+                .group_by_path(PATH_GROUP_DEPTH)
+                .into_iter()
+                .collect();
+            display_groups(out, groups, sort, collapse_below, explain, width)?
+        }
+    }
+
+    writeln!(
+        out,
+        "\n{}",
+        "Run 'cleanser clean --risk <level>' to clean files".cyan()
+    )?;
+
+    Ok(())
+}
+
+/// The default report: risk levels, safest first, each broken down by category
+fn display_by_risk(
+    out: &mut dyn Write,
+    results: &ScanResults,
+    sort: SortOrder,
+    collapse_below: u64,
+    explain: bool,
+    width: usize,
+) -> io::Result<()> {
+    let by_risk = results.group_by_risk();
+    for risk in RiskLevel::all() {
+        if let Some(items) = by_risk.get(&risk) {
+            let total: u64 = items.iter().map(|i| i.size).sum();
+            let estimated = items.iter().any(|i| i.estimated);
+
+            let risk_color = match risk {
+                RiskLevel::Safe => "green",
+                RiskLevel::Moderate => "yellow",
+                RiskLevel::Risky => "red",
+                RiskLevel::Manual => "magenta",
+            };
+
+            writeln!(
+                out,
+                "{} ({}, {} items)",
+                format!("{:?} Risk", risk).color(risk_color).bold(),
+                format_total(total, estimated).bold(),
+                items.len()
+            )?;
+
+            // Group by category within risk level, largest first, so the
+            // report reads the same on every run
+            let mut by_category: Vec<_> = group_by_category(items.iter().copied())
+                .into_iter()
+                .collect();
+            by_category.sort_by_cached_key(|(_, cat_items)| {
+                Reverse(cat_items.iter().map(|i| i.size).sum::<u64>())
+            });
+
+            // Categories below the threshold are folded into one line
+            let mut other_categories = 0;
+            let mut other_items: Vec<&CleanableItem> = Vec::new();
+
+            for (category, cat_items) in by_category {
+                let cat_total: u64 = cat_items.iter().map(|i| i.size).sum();
+                let cat_estimated = cat_items.iter().any(|i| i.estimated);
+                if cat_total < collapse_below {
+                    other_categories += 1;
+                    other_items.extend(cat_items);
+                    continue;
+                }
+                writeln!(
+                    out,
+                    "  {} - {} ({} items)",
+                    category,
+                    format_total(cat_total, cat_estimated),
+                    cat_items.len()
+                )?;
+
+                display_items(out, &cat_items, 4, sort, explain, width)?;
+            }
+
+            if !other_items.is_empty() {
+                writeln!(
+                    out,
+                    "  Other - {} ({} items in {} categories)",
+                    format_total(
+                        other_items.iter().map(|i| i.size).sum(),
+                        other_items.iter().any(|i| i.estimated)
+                    ),
+                    other_items.len(),
+                    other_categories
+                )?;
+            }
+            writeln!(out)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// The report grouped by category or directory: one heading per group,
+/// largest first, with groups below `collapse_below` folded into "Other"
+fn display_groups(
+    out: &mut dyn Write,
+    groups: Vec<(String, Vec<&CleanableItem>)>,
+    sort: SortOrder,
+    collapse_below: u64,
+    explain: bool,
+    width: usize,
+) -> io::Result<()> {
+    let group_size = |items: &[&CleanableItem]| items.iter().map(|i| i.size).sum::<u64>();
+    let mut groups = groups;
+    groups.sort_by(|(a, a_items), (b, b_items)| {
+        group_size(b_items)
+            .cmp(&group_size(a_items))
+            .then_with(|| a.cmp(b))
+    });
+
+    let mut other_groups = 0;
+    let mut other_items: Vec<&CleanableItem> = Vec::new();
+    for (label, items) in groups {
+        let total = group_size(&items);
+        if total < collapse_below {
+            other_groups += 1;
+            other_items.extend(items);
+            continue;
+        }
+
+        writeln!(
+            out,
+            "{} ({}, {} items)",
+            label.bold(),
+            format_total(total, items.iter().any(|i| i.estimated)).bold(),
+            items.len()
+        )?;
+        display_items(out, &items, 2, sort, explain, width)?;
+        writeln!(out)?;
+    }
+
+    if !other_items.is_empty() {
+        writeln!(
+            out,
+            "Other - {} ({} items in {} groups)\n",
+            format_total(
+                group_size(&other_items),
+                other_items.iter().any(|i| i.estimated)
+            ),
+            other_items.len(),
+            other_groups
+        )?;
+    }
+
+    Ok(())
+}
+
+/// List a group's largest (or oldest) three items, or all of them with the
+/// reason each was flagged when explaining, indented by `indent` spaces
+fn display_items(
+    out: &mut dyn Write,
+    items: &[&CleanableItem],
+    indent: usize,
+    sort: SortOrder,
+    explain: bool,
+    width: usize,
+) -> io::Result<()> {
+    let pad = " ".repeat(indent);
+    let shown = if explain { items.len() } else { 3 };
+    let mut sorted_items = items.to_vec();
+    sort_items(&mut sorted_items, sort);
+    for item in sorted_items.iter().take(shown) {
+        let mut details = Vec::new();
+        if !item.members.is_empty() {
+            details.push(format!("{} paths", item.members.len()));
+        }
+        if let Some(project) = &item.project {
+            details.push(format!("project: {}", project));
+        }
+        if let Some(modified) = item.last_modified {
+            details.push(format!("modified {}", format_age(modified)));
+        }
+
+        let size = format_item_size(item);
+        let details = details.join(", ");
+        // Shorten the path so the whole line fits on one row
+        let mut taken = indent + size.chars().count() + 3;
+        if !details.is_empty() {
+            taken += details.chars().count() + 3;
+        }
+        let path = elide_middle(&item.path, width.saturating_sub(taken).max(MIN_PATH_WIDTH));
+
+        if details.is_empty() {
+            writeln!(out, "{}{} - {}", pad, size, path.dimmed())?;
+        } else {
+            writeln!(
+                out,
+                "{}{} - {} ({})",
+                pad,
+                size,
+                path.dimmed(),
+                details.dimmed()
+            )?;
+        }
+        if explain {
+            writeln!(out, "{}  {}", pad, item.description.dimmed())?;
+            if let Some(reason) = &item.reason {
+                writeln!(out, "{}  {} {}", pad, "why:".cyan(), reason.dimmed())?;
+            }
+        }
+    }
+    if items.len() > shown {
+        writeln!(out, "{}... and {} more", pad, items.len() - shown)?;
+    }
+
+    Ok(())
+}
+
+/// Path components below the scanned path that name a group for
+/// `--group-by path`: `~/Library/Caches`, `~/code/project`
+const PATH_GROUP_DEPTH: usize = 2;
+
+/// Write a one-line total with subtotals per risk level
+pub fn display_summary(out: &mut dyn Write, results: &ScanResults) -> io::Result<()> {
+    let by_risk = results.total_by_risk();
+    let subtotals: Vec<String> = RiskLevel::all()
+        .map(|risk| {
+            let total = by_risk.get(&risk).copied().unwrap_or_default();
+            let estimated = results
+                .items
+                .iter()
+                .any(|item| item.risk_level == risk && item.estimated);
+            format!("{:?}: {}", risk, format_total(total, estimated))
+        })
+        .collect();
+
+    writeln!(
+        out,
+        "Reclaimable: {} across {} items ({})",
+        format_total(
+            results.total_size,
+            results.items.iter().any(|item| item.estimated)
+        ),
+        results.items.len(),
+        subtotals.join(", ")
+    )
+}
+
+/// Item size ranges shown by `--histogram`, each up to (but excluding) its bound
+const HISTOGRAM_BUCKETS: &[(&str, u64)] = &[
+    ("< 100 MB", 100 * 1024 * 1024),
+    ("100 MB - 1 GB", 1024 * 1024 * 1024),
+    ("1 - 10 GB", 10 * 1024 * 1024 * 1024),
+    ("> 10 GB", u64::MAX),
+];
+
+/// Widest bar in the histogram, in characters
+const HISTOGRAM_WIDTH: u64 = 40;
+
+/// Write a histogram of items bucketed by size, with bars proportional to
+/// the bytes in each bucket
+pub fn display_histogram(out: &mut dyn Write, results: &ScanResults) -> io::Result<()> {
+    let mut buckets = vec![(0usize, 0u64); HISTOGRAM_BUCKETS.len()];
+    for item in &results.items {
+        let index = HISTOGRAM_BUCKETS
+            .iter()
+            .position(|(_, bound)| item.size < *bound)
+            .unwrap_or(HISTOGRAM_BUCKETS.len() - 1);
+        buckets[index].0 += 1;
+        buckets[index].1 += item.size;
+    }
+
+    let largest = buckets.iter().map(|(_, bytes)| *bytes).max().unwrap_or(0);
+
+    writeln!(out, "\n{}", "=== Size Distribution ===".green().bold())?;
+    for ((label, _), (count, bytes)) in HISTOGRAM_BUCKETS.iter().zip(buckets) {
+        let width = if largest == 0 {
+            0
+        } else {
+            // Round up so any non-empty bucket gets at least one character
+            (bytes as u128 * HISTOGRAM_WIDTH as u128).div_ceil(largest as u128) as usize
+        };
+        writeln!(
+            out,
+            "  {:<14} {:<40} {:>11} ({} items)",
+            label,
+            "█".repeat(width).cyan(),
+            format_size(bytes, BINARY),
+            count
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write one row per item, with a header row
+pub fn write_csv(out: &mut dyn Write, results: &ScanResults, sort: SortOrder) -> io::Result<()> {
+    writeln!(
+        out,
+        "path,size,estimated,category,risk_level,description,last_modified,project,reason"
+    )?;
+
+    let mut items: Vec<&CleanableItem> = results.items.iter().collect();
+    sort_items(&mut items, sort);
+    for item in items {
+        let category = item.category.machine_name();
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{}",
+            csv_field(&item.path),
+            item.size,
+            item.estimated,
+            csv_field(&category),
+            item.risk_level,
+            csv_field(&item.description),
+            item.last_modified
+                .map(|t| t.to_string())
+                .unwrap_or_default(),
+            csv_field(item.project.as_deref().unwrap_or_default()),
+            csv_field(item.reason.as_deref().unwrap_or_default())
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Write one `<size>\t<path>` line per item, with sizes as `du -h` prints them
+pub fn write_du(out: &mut dyn Write, results: &ScanResults, sort: SortOrder) -> io::Result<()> {
+    let mut items: Vec<&CleanableItem> = results.items.iter().collect();
+    sort_items(&mut items, sort);
+    for item in items {
+        writeln!(out, "{}\t{}", du_size(item.size), item.path)?;
+    }
+
+    Ok(())
+}
+
+/// Write the items as a plain table with aligned columns: size (right
+/// aligned), risk, category and path. With a `width`, long paths are cut
+/// from the left so each row fits; without one they're printed whole.
+pub fn write_table(
+    out: &mut dyn Write,
+    results: &ScanResults,
+    sort: SortOrder,
+    width: Option<usize>,
+) -> io::Result<()> {
+    let mut items: Vec<&CleanableItem> = results.items.iter().collect();
+    sort_items(&mut items, sort);
+
+    let header = ["Size", "Risk", "Category", "Path"];
+    let rows: Vec<[String; 4]> = items
+        .iter()
+        .map(|item| {
+            [
+                format_total(item.size, item.estimated),
+                item.risk_level.to_string(),
+                item.category.to_string(),
+                item.path.clone(),
+            ]
+        })
+        .collect();
+
+    let mut widths = header.map(|title| title.chars().count());
+    for row in &rows {
+        for (column, cell) in row.iter().enumerate() {
+            widths[column] = widths[column].max(cell.chars().count());
+        }
+    }
+
+    // Room left for the path after the other columns and their separators,
+    // but never so little that the path becomes unrecognisable
+    let path_width = width.map(|width| {
+        width
+            .saturating_sub(widths[..3].iter().sum::<usize>() + 3 * 2)
+            .max(MIN_PATH_WIDTH)
+    });
+
+    writeln!(
+        out,
+        "{:>size_width$}  {:<risk_width$}  {:<category_width$}  {}",
+        header[0],
+        header[1],
+        header[2],
+        header[3],
+        size_width = widths[0],
+        risk_width = widths[1],
+        category_width = widths[2],
+    )?;
+    for [size, risk, category, path] in &rows {
+        let path = match path_width {
+            Some(path_width) => elide_left(path, path_width),
+            None => path.clone(),
+        };
+        writeln!(
+            out,
+            "{:>size_width$}  {:<risk_width$}  {:<category_width$}  {}",
+            size,
+            risk,
+            category,
+            path,
+            size_width = widths[0],
+            risk_width = widths[1],
+            category_width = widths[2],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Shortened paths keep at least this many characters, however narrow the
+/// terminal, so they stay recognisable
+const MIN_PATH_WIDTH: usize = 20;
+
+/// Shorten `text` to at most `max` characters by replacing its start with an
+/// ellipsis, keeping the end, which for a path is the part that identifies it
+fn elide_left(text: &str, max: usize) -> String {
+    let length = text.chars().count();
+    if length <= max {
+        return text.to_string();
+    }
+
+    let keep = max.saturating_sub(1);
+    let tail: String = text.chars().skip(length - keep).collect();
+    format!("…{}", tail)
+}
+
+/// Shorten `path` to at most `max` characters by replacing its middle with
+/// an ellipsis, cutting at separators where possible so the start and the
+/// last components stay whole (`/Users/me/…/Caches/foo`)
+fn elide_middle(path: &str, max: usize) -> String {
+    let chars: Vec<char> = path.chars().collect();
+    if chars.len() <= max {
+        return path.to_string();
+    }
+
+    // The end of a path identifies it best, so it gets two thirds of the room
+    let budget = max.saturating_sub(1);
+    let head_len = budget / 3;
+    let tail_len = budget - head_len;
+
+    let mut head = &chars[..head_len];
+    if let Some(slash) = head.iter().rposition(|&c| c == '/') {
+        head = &head[..=slash];
+    }
+    let mut tail = &chars[chars.len() - tail_len..];
+    if let Some(slash) = tail.iter().position(|&c| c == '/') {
+        tail = &tail[slash..];
+    }
+
+    let head: String = head.iter().collect();
+    let tail: String = tail.iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+/// Format a size like BSD `du -h`: three significant digits at most, rounded
+/// up, with a one-letter unit (`512B`, `4.0K`, `1.5M`, `12G`)
+fn du_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["B", "K", "M", "G", "T", "P"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    let tenths = (value * 10.0).ceil() / 10.0;
+    if unit == 0 {
+        format!("{}B", bytes)
+    } else if tenths < 10.0 {
+        format!("{:.1}{}", tenths, UNITS[unit])
+    } else {
+        format!("{:.0}{}", value.ceil(), UNITS[unit])
+    }
+}
+
+/// Quote a CSV field when it contains a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Print how long each scan phase took to stderr, slowest first
+pub fn display_profile(profiles: &[PhaseProfile], total: Duration) {
+    let mut profiles: Vec<&PhaseProfile> = profiles.iter().collect();
+    profiles.sort_by_key(|profile| std::cmp::Reverse(profile.elapsed));
+
+    eprintln!("\n{}", "=== Scan Profile ===".green().bold());
+    eprintln!(
+        "{:<48} {:>9} {:>7} {:>11} {:>9} {:>6} {:>11}",
+        "Phase", "Time", "Items", "Found", "Sizing", "Dirs", "Hashed"
+    );
+    for profile in profiles {
+        eprintln!(
+            "{:<48} {:>8.2}s {:>7} {:>11} {:>8.2}s {:>6} {:>11}",
+            profile.phase.trim_end_matches("..."),
+            profile.elapsed.as_secs_f64(),
+            profile.items,
+            format_size(profile.bytes_found, BINARY),
+            profile.sizing.as_secs_f64(),
+            profile.dirs_sized,
+            format_size(profile.bytes_hashed, BINARY)
+        );
+    }
+    eprintln!("{:<48} {:>8.2}s", "Total".bold(), total.as_secs_f64());
+}
+
+/// List named snapshots with when they were taken and what they found
+pub fn display_snapshots(snapshots: &[cache::Snapshot]) {
+    if snapshots.is_empty() {
+        println!("No snapshots saved yet (use `cleanser scan --save-as <NAME>`)");
+        return;
+    }
+
+    let name_width = snapshots.iter().map(|s| s.name.len()).max().unwrap_or(0);
+    for snapshot in snapshots {
+        println!(
+            "{:<name_width$}  {:<14} {:>10}  {} items",
+            snapshot.name.bold(),
+            format_snapshot_age(snapshot.summary.timestamp),
+            format_size(snapshot.summary.total_size, BINARY),
+            snapshot.summary.item_count,
+        );
+    }
+}
+
+/// Like `format_age`, but down to the minute for snapshots taken today
+fn format_snapshot_age(timestamp: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(timestamp);
+
+    match now.saturating_sub(timestamp) {
+        age if age < 60 => "just now".to_string(),
+        age if age < 3600 => format!("{} min ago", age / 60),
+        age if age < 86400 => format!("{} h ago", age / 3600),
+        _ => format_age(timestamp),
+    }
+}
+
+pub fn display_stats(results: &ScanResults, history: &[cache::ScanSummary]) {
+    println!("\n{}", "=== Cleanser Stats ===".green().bold());
+    println!(
+        "Total reclaimable: {} ({} items)",
+        format_size(results.total_size, BINARY).bold(),
+        results.items.len()
+    );
+
+    println!("\n{}", "By risk:".bold());
+    let by_risk = results.total_by_risk();
+    for risk in RiskLevel::all() {
+        let total = by_risk.get(&risk).copied().unwrap_or_default();
+        println!("  {:<10} {}", risk.to_string(), format_size(total, BINARY));
+    }
+
+    // Largest first; the sort is stable, so ties keep category order
+    let mut categories: Vec<_> = results.total_by_category().into_iter().collect();
+    categories.sort_by_key(|(_, total)| std::cmp::Reverse(*total));
+
+    println!("\n{}", "By category:".bold());
+    for (category, total) in categories {
+        println!(
+            "  {:<20} {}",
+            category.to_string(),
+            format_size(total, BINARY)
+        );
+    }
+
+    if let Some(largest) = results.items.iter().max_by_key(|i| i.size) {
+        println!(
+            "\n{} {} - {} ({})",
+            "Largest item:".bold(),
+            format_size(largest.size, BINARY),
+            largest.path.dimmed(),
+            largest.category
+        );
+    }
+
+    // Show how reclaimable space has moved over the last few scans
+    if history.len() > 1 {
+        let recent = &history[history.len().saturating_sub(5)..];
+        let trend: Vec<String> = recent
+            .iter()
+            .map(|s| format_size(s.total_size, BINARY))
+            .collect();
+        println!(
+            "\n{} {}",
+            format!("Trend (last {} scans):", recent.len()).bold(),
+            trend.join(" → ")
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(path: &str, category: CleanCategory, size: u64) -> CleanableItem {
+        CleanableItem {
+            path: path.to_string(),
+            members: Vec::new(),
+            size,
+            estimated: false,
+            apparent_size: None,
+            linked_size: None,
+            category,
+            risk_level: RiskLevel::Safe,
+            description: String::new(),
+            last_modified: None,
+            project: None,
+            reason: None,
+        }
+    }
+
+    /// Small xorshift generator, so the property tests are reproducible
+    /// without pulling in a random number crate
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: usize) -> usize {
+            (self.next() % n as u64) as usize
+        }
+    }
+
+    // A root that doesn't exist, so canonicalizing leaves the paths alone
+    const ROOT: &str = "/cleanser-test-missing-root";
+
+    /// A scratch directory removed when dropped
+    struct TestDir(PathBuf);
+
+    impl TestDir {
+        fn new(name: &str) -> Self {
+            let dir =
+                std::env::temp_dir().join(format!("cleanser-test-{}-{}", std::process::id(), name));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            // Resolve /tmp -> /private/tmp and the like up front, as the
+            // scanner does for the paths it reports
+            TestDir(fs::canonicalize(&dir).unwrap())
+        }
+
+        fn write(&self, relative: &str, len: usize) -> PathBuf {
+            let path = self.0.join(relative);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, vec![b'x'; len]).unwrap();
+            path
+        }
+
+        fn path(&self) -> String {
+            self.0.display().to_string()
+        }
+    }
+
+    impl Drop for TestDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// A scan of `path` running only `phases`, with every size threshold low
+    fn scan_config(path: &str, phases: &[ScanPhase]) -> ScanConfig {
+        ScanConfig {
+            speed: ScanSpeed::Normal,
+            paths: vec![path.to_string()],
+            min_file_size_mb: 1,
+            extensions: Vec::new(),
+            excluded_extensions: Vec::new(),
+            min_dir_size_mb: 0,
+            log_min_size_mb: 0,
+            max_depth: None,
+            find_duplicates: phases.contains(&ScanPhase::Duplicates),
+            dup_min_size_kb: 1,
+            hash_threads: Some(1),
+            follow_symlinks: false,
+            estimate: false,
+            apparent_size: true,
+            dereference_size: false,
+            strict_paths: true,
+            enabled_phases: phases.iter().copied().collect(),
+            skip_dirty_repos: false,
+            repo_activity: false,
+            custom_rules: Vec::new(),
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn deduplicate_nested_paths_keeps_only_outermost_items() {
+        // Few, prefix-sharing names make nesting, repeats and `foo`/`foobar`
+        // style siblings common
+        const NAMES: [&str; 5] = ["a", "b", "foo", "foobar", "foo.d"];
+
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let count = 1 + rng.below(12);
+            let input: Vec<String> = (0..count)
+                .map(|_| {
+                    let depth = 1 + rng.below(4);
+                    let mut path = PathBuf::from(ROOT);
+                    for _ in 0..depth {
+                        path.push(NAMES[rng.below(NAMES.len())]);
+                    }
+                    path.display().to_string()
+                })
+                .collect();
+
+            let kept: Vec<String> = deduplicate_nested_paths(
+                input
+                    .iter()
+                    .map(|path| item(path, CleanCategory::SystemCache, 1))
+                    .collect(),
+            )
+            .into_iter()
+            .map(|item| item.path)
+            .collect();
+
+            for (i, a) in kept.iter().enumerate() {
+                for (j, b) in kept.iter().enumerate() {
+                    assert!(
+                        i == j || !Path::new(b).starts_with(a),
+                        "{} kept inside {} from {:?}",
+                        b,
+                        a,
+                        input
+                    );
+                }
+            }
+            for path in &input {
+                let nested = input
+                    .iter()
+                    .any(|other| other != path && Path::new(path).starts_with(other));
+                assert!(
+                    nested || kept.contains(path),
+                    "{} dropped from {:?}",
+                    path,
+                    input
+                );
+                assert!(
+                    kept.iter().any(|k| Path::new(path).starts_with(k)),
+                    "{} not covered by {:?}",
+                    path,
+                    kept
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn nested_cache_is_dropped_in_favour_of_its_node_modules() {
+        let node_modules = format!("{}/proj/node_modules", ROOT);
+        let cache = format!("{}/.cache", node_modules);
+
+        let kept = deduplicate_nested_paths(vec![
+            item(&cache, CleanCategory::SystemCache, 40),
+            item(&node_modules, CleanCategory::BuildArtifacts, 100),
+        ]);
+
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].path, node_modules);
+        assert_eq!(kept[0].category, CleanCategory::BuildArtifacts);
+        assert_eq!(kept[0].size, 100);
+    }
+
+    #[test]
+    fn item_reached_through_a_symlinked_ancestor_is_kept_once() {
+        let dir = TestDir::new("aliased-item");
+        let cache = dir.0.join("real/cache");
+        fs::create_dir_all(&cache).unwrap();
+        std::os::unix::fs::symlink(dir.0.join("real"), dir.0.join("alias")).unwrap();
+        let alias = dir.0.join("alias/cache");
+
+        let kept = deduplicate_nested_paths(vec![
+            item(&alias.display().to_string(), CleanCategory::SystemCache, 1),
+            item(&cache.display().to_string(), CleanCategory::SystemCache, 1),
+        ]);
+
+        assert_eq!(kept.len(), 1, "{:?}", kept);
+        assert_eq!(kept[0].path, cache.display().to_string());
+    }
+
+    #[test]
+    fn sibling_sharing_a_name_prefix_is_not_nested() {
+        let foo = format!("{}/foo", ROOT);
+        let foobar = format!("{}/foobar", ROOT);
+
+        let kept = deduplicate_nested_paths(vec![
+            item(&foobar, CleanCategory::SystemCache, 1),
+            item(&foo, CleanCategory::SystemCache, 1),
+        ]);
+
+        let kept: Vec<&str> = kept.iter().map(|item| item.path.as_str()).collect();
+        assert_eq!(kept, [foo.as_str(), foobar.as_str()]);
+    }
+
+    #[test]
+    fn aggregate_members_inside_a_reported_directory_are_counted_once() {
+        let dir = TestDir::new("covered-members");
+        let kept = dir.write("keep/.DS_Store", 10);
+        dir.write("scratch/.DS_Store", 20);
+        dir.write("scratch/big.bin", 100);
+
+        let mut config = scan_config(&dir.path(), &[ScanPhase::Clutter, ScanPhase::CustomRules]);
+        config.custom_rules = vec![CustomRule {
+            pattern: "**/scratch".to_string(),
+            category: CleanCategory::TempFiles,
+            risk: RiskLevel::Safe,
+            description: None,
+        }];
+        let results = scan(config.clone(), None).unwrap();
+
+        let clutter = results
+            .items
+            .iter()
+            .find(|item| !item.members.is_empty())
+            .expect(".DS_Store outside scratch not reported");
+        assert_eq!(clutter.members, [kept.display().to_string()]);
+        assert_eq!(clutter.size, 10);
+        assert_eq!(results.total_size, 130);
+
+        // Nothing left once every member is covered
+        fs::remove_dir_all(dir.0.join("keep")).unwrap();
+        let results = scan(config, None).unwrap();
+        assert_eq!(results.items.len(), 1, "{:?}", results.items);
+        assert_eq!(results.total_size, 120);
+    }
+
+    #[test]
+    fn large_file_that_is_also_a_duplicate_is_counted_once() {
+        const LEN: usize = 2 * 1024 * 1024;
+        let dir = TestDir::new("large-duplicate");
+        dir.write("original.bin", LEN);
+        dir.write("copy.bin", LEN);
+
+        let results = scan(
+            scan_config(&dir.path(), &[ScanPhase::LargeFiles, ScanPhase::Duplicates]),
+            None,
+        )
+        .unwrap();
+
+        // Either copy may be the one reported as the duplicate; the other is
+        // only a large file, and neither appears twice
+        let mut paths: Vec<&str> = results.items.iter().map(|i| i.path.as_str()).collect();
+        paths.sort();
+        paths.dedup();
+        assert_eq!(paths.len(), 2);
+        assert_eq!(results.items.len(), 2);
+        assert_eq!(
+            results
+                .items
+                .iter()
+                .filter(|item| item.category == CleanCategory::DuplicateFiles)
+                .count(),
+            1
+        );
+        assert_eq!(results.total_size, 2 * LEN as u64);
+    }
+
+    fn reports(results: &ScanResults, path: &Path) -> bool {
+        let path = path.display().to_string();
+        results
+            .items
+            .iter()
+            .any(|item| item.path == path || item.members.contains(&path))
+    }
+
+    #[test]
+    fn deep_log_file_is_found_by_a_thorough_scan_only() {
+        let dir = TestDir::new("deep-log");
+        let log = dir.write("Library/Logs/App/a/b/c/d/deep.log", 10);
+
+        for (speed, found) in [(ScanSpeed::Thorough, true), (ScanSpeed::Quick, false)] {
+            let mut config = scan_config(&dir.path(), &[ScanPhase::Logs]);
+            config.speed = speed;
+            let results = scan(config, None).unwrap();
+
+            assert_eq!(reports(&results, &log), found, "{:?} scan", speed);
+        }
+    }
+
+    #[test]
+    fn every_search_phase_honours_max_depth() {
+        // Each case puts what the phase looks for three levels below the
+        // scan path, which --max-depth 3 reaches and --max-depth 2 doesn't
+        type Files = &'static [(&'static str, usize)];
+        let cases: [(ScanPhase, Files, &str); 7] = [
+            (
+                ScanPhase::Caches,
+                &[("a/b/appcache/data", 10)],
+                "a/b/appcache",
+            ),
+            (
+                ScanPhase::BuildArtifacts,
+                &[("a/b/node_modules/index.js", 10)],
+                "a/b/node_modules",
+            ),
+            (ScanPhase::Logs, &[("logs/a/app.log", 10)], "logs/a/app.log"),
+            (
+                ScanPhase::LargeFiles,
+                &[("a/b/big.bin", 2 * 1024 * 1024)],
+                "a/b/big.bin",
+            ),
+            (
+                ScanPhase::Duplicates,
+                &[("a/b/one.bin", 4096), ("a/b/two.bin", 4096)],
+                "a/b/two.bin",
+            ),
+            (
+                ScanPhase::Clutter,
+                &[("a/b/.DS_Store", 10)],
+                "a/b/.DS_Store",
+            ),
+            (
+                ScanPhase::CustomRules,
+                &[("a/b/scratch.tmp", 10)],
+                "a/b/scratch.tmp",
+            ),
+        ];
+
+        for (phase, files, expected) in cases {
+            let dir = TestDir::new(&format!("depth-{:?}", phase));
+            for (file, len) in files {
+                dir.write(file, *len);
+            }
+            let expected = dir.0.join(expected);
+            let duplicate = dir.0.join("a/b/one.bin");
+
+            for (max_depth, found) in [(3, true), (2, false)] {
+                let mut config = scan_config(&dir.path(), &[phase]);
+                config.max_depth = Some(max_depth);
+                config.custom_rules = vec![CustomRule {
+                    pattern: "**/*.tmp".to_string(),
+                    category: CleanCategory::TempFiles,
+                    risk: RiskLevel::Safe,
+                    description: None,
+                }];
+                let results = scan(config, None).unwrap();
+
+                // Either copy of a duplicate may be the one reported
+                let reported = reports(&results, &expected)
+                    || (phase == ScanPhase::Duplicates && reports(&results, &duplicate));
+                assert_eq!(
+                    reported, found,
+                    "{:?} with --max-depth {}: {:?}",
+                    phase, max_depth, results.items
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn logs_are_attributed_by_location() {
+        let dir = TestDir::new("log-attribution");
+        let logs = [
+            dir.write("Library/Logs/App/app.log", 10),
+            dir.write("logs/server.log", 10),
+            dir.write(".logs/worker.log", 10),
+        ];
+
+        let results = scan(scan_config(&dir.path(), &[ScanPhase::Logs]), None).unwrap();
+        for log in &logs {
+            let item = results
+                .items
+                .iter()
+                .find(|item| Path::new(&item.path) == log)
+                .unwrap_or_else(|| panic!("{} not reported", log.display()));
+            assert_eq!(item.category, CleanCategory::AppLogs);
+            assert_eq!(item.risk_level, RiskLevel::Safe);
+        }
+
+        // The system folders only come along with a home folder's own logs
+        let system = |paths: &[String]| -> Vec<(PathBuf, usize, CleanCategory, RiskLevel)> {
+            log_locations(paths)
+                .into_iter()
+                .filter(|(_, _, category, _)| *category == CleanCategory::SystemLogs)
+                .collect()
+        };
+        let with_home = system(&[dir.path()]);
+        assert_eq!(with_home.len(), SYSTEM_LOG_DIRS.len());
+        for ((path, depth, _, risk), expected) in with_home.iter().zip(SYSTEM_LOG_DIRS) {
+            assert_eq!(path, Path::new(expected));
+            assert_eq!(*depth, 2);
+            assert_eq!(*risk, RiskLevel::Moderate);
+        }
+        assert!(system(&[dir.0.join("logs").display().to_string()]).is_empty());
+    }
+
+    #[test]
+    fn log_file_pattern_matches_rotated_and_compressed_logs() {
+        let log_regex = Regex::new(LOG_FILE_PATTERN).unwrap();
+        for name in [
+            "foo.log",
+            "foo.out",
+            "foo.log.3",
+            "foo.log.gz",
+            "foo.log.1.gz",
+            "FOO.LOG",
+        ] {
+            assert!(log_regex.is_match(name), "{} should match", name);
+        }
+        for name in [
+            "catalog.json",
+            "foo.logger",
+            "login",
+            "changelog.md",
+            "foo.gz",
+        ] {
+            assert!(!log_regex.is_match(name), "{} shouldn't match", name);
+        }
+    }
+
+    #[test]
+    fn project_directories_named_like_caches_are_not_flagged() {
+        let dir = TestDir::new("cache-names");
+        // A project whose name ends in "cache", and one that only mentions it
+        dir.write("code/image-cache/package.json", 10);
+        dir.write("code/image-cache/index.js", 10);
+        dir.write("code/my-image-cache-lib/src/lib.rs", 10);
+        // A real cache, as a control
+        dir.write("code/tool/.cache/blob", 10);
+
+        let results = scan(scan_config(&dir.path(), &[ScanPhase::Caches]), None).unwrap();
+
+        assert!(!reports(&results, &dir.0.join("code/image-cache")));
+        assert!(!reports(&results, &dir.0.join("code/my-image-cache-lib")));
+        assert!(reports(&results, &dir.0.join("code/tool/.cache")));
+    }
+
+    #[test]
+    fn discovery_walks_stop_at_bundles() {
+        let dir = TestDir::new("bundle");
+        dir.write("Tool.app/Contents/MacOS/tool", 10);
+        dir.write("Tool.app/Contents/Resources/cache/data", 10);
+        dir.write("plain/file", 10);
+
+        let walked = |into_bundles: bool| -> Vec<PathBuf> {
+            walk_dir(&dir.0, usize::MAX, false, into_bundles, &[])
+                .map(|entry| entry.path().to_path_buf())
+                .collect()
+        };
+
+        let discovered = walked(false);
+        assert!(discovered.contains(&dir.0.join("Tool.app")));
+        assert!(discovered.contains(&dir.0.join("plain/file")));
+        assert!(
+            !discovered
+                .iter()
+                .any(|path| path.starts_with(dir.0.join("Tool.app/Contents"))),
+            "{:?}",
+            discovered
+        );
+
+        // Measuring a bundle still walks all of it
+        assert!(walked(true).contains(&dir.0.join("Tool.app/Contents/MacOS/tool")));
+    }
+
+    #[test]
+    fn large_files_inside_a_bundle_are_not_reported() {
+        const LEN: usize = 2 * 1024 * 1024;
+        let dir = TestDir::new("bundle-large-files");
+        dir.write("Tool.app/Contents/MacOS/tool", LEN);
+        dir.write("Tool.app/Contents/Resources/model.bin", LEN);
+        let outside = dir.write("plain/big.bin", LEN);
+
+        let results = scan(scan_config(&dir.path(), &[ScanPhase::LargeFiles]), None).unwrap();
+
+        assert!(reports(&results, &outside), "{:?}", results.items);
+        let contents = dir.0.join("Tool.app/Contents");
+        assert!(
+            !results
+                .items
+                .iter()
+                .any(|item| Path::new(&item.path).starts_with(&contents)),
+            "{:?}",
+            results.items
+        );
+    }
 }
@@ -0,0 +1,140 @@
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const LABEL: &str = "com.cleanser.scan";
+const LAUNCH_AGENTS_DIR: &str = "Library/LaunchAgents";
+const LOG_FILE: &str = "Library/Logs/cleanser.log";
+
+/// What the scheduled job runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduledTask {
+    /// `cleanser scan`, refreshing the cache and history, optionally with
+    /// `--notify-threshold` (in bytes)
+    Scan { notify_threshold: Option<u64> },
+    /// `cleanser clean --yes`, deleting Safe items
+    Clean,
+}
+
+/// Parse a time of day such as `3:00` or `23:30` into (hour, minute)
+pub fn parse_time(time: &str) -> Result<(u8, u8), String> {
+    let invalid = || format!("'{}' is not a time like 03:00 or 23:30", time);
+
+    let (hour, minute) = time.trim().split_once(':').ok_or_else(invalid)?;
+    let hour: u8 = hour.parse().map_err(|_| invalid())?;
+    let minute: u8 = minute.parse().map_err(|_| invalid())?;
+    if hour > 23 || minute > 59 {
+        return Err(invalid());
+    }
+
+    Ok((hour, minute))
+}
+
+/// Get the launchd agent path
+pub fn get_plist_path() -> Result<PathBuf> {
+    let home = std::env::var("HOME")?;
+    Ok(PathBuf::from(home)
+        .join(LAUNCH_AGENTS_DIR)
+        .join(format!("{}.plist", LABEL)))
+}
+
+/// Write a launchd agent running `task` daily at `hour:minute` and load it,
+/// replacing any previously installed schedule. Returns the plist path.
+pub fn install(task: ScheduledTask, hour: u8, minute: u8) -> Result<PathBuf> {
+    let plist_path = get_plist_path()?;
+    let log_path = PathBuf::from(std::env::var("HOME")?).join(LOG_FILE);
+    let exe = std::env::current_exe().context("Failed to locate the cleanser binary")?;
+
+    if plist_path.exists() {
+        // Unloading a job that isn't loaded fails harmlessly
+        let _ = launchctl("unload", &plist_path);
+    }
+
+    if let Some(parent) = plist_path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    fs::write(&plist_path, plist(task, &exe, &log_path, hour, minute))
+        .with_context(|| format!("Failed to write {:?}", plist_path))?;
+
+    launchctl("load", &plist_path)?;
+    Ok(plist_path)
+}
+
+/// Unload and delete the launchd agent. Returns false if none was installed.
+pub fn uninstall() -> Result<bool> {
+    let plist_path = get_plist_path()?;
+    if !plist_path.exists() {
+        return Ok(false);
+    }
+
+    launchctl("unload", &plist_path)?;
+    fs::remove_file(&plist_path).with_context(|| format!("Failed to remove {:?}", plist_path))?;
+    Ok(true)
+}
+
+fn launchctl(action: &str, plist_path: &Path) -> Result<()> {
+    let status = Command::new("launchctl")
+        .arg(action)
+        .arg(plist_path)
+        .status()
+        .context("Failed to run launchctl")?;
+
+    if !status.success() {
+        bail!("launchctl {} failed ({})", action, status);
+    }
+
+    Ok(())
+}
+
+fn plist(task: ScheduledTask, exe: &Path, log_path: &Path, hour: u8, minute: u8) -> String {
+    let mut args: Vec<String> = match task {
+        ScheduledTask::Scan { .. } => vec!["--quiet".into(), "scan".into()],
+        ScheduledTask::Clean => vec!["--quiet".into(), "clean".into(), "--yes".into()],
+    };
+    if let ScheduledTask::Scan {
+        notify_threshold: Some(threshold),
+    } = task
+    {
+        args.push("--notify-threshold".into());
+        args.push(format!("{}B", threshold));
+    }
+    let program_arguments: String = std::iter::once(exe.to_string_lossy().into_owned())
+        .chain(args)
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(&arg)))
+        .collect();
+    let log_path = xml_escape(&log_path.to_string_lossy());
+
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{LABEL}</string>
+    <key>ProgramArguments</key>
+    <array>
+{program_arguments}    </array>
+    <key>StartCalendarInterval</key>
+    <dict>
+        <key>Hour</key>
+        <integer>{hour}</integer>
+        <key>Minute</key>
+        <integer>{minute}</integer>
+    </dict>
+    <key>StandardOutPath</key>
+    <string>{log_path}</string>
+    <key>StandardErrorPath</key>
+    <string>{log_path}</string>
+</dict>
+</plist>
+"#
+    )
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
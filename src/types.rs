@@ -1,8 +1,13 @@
 use clap::ValueEnum;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ScanSpeed {
     /// Quick scan - only common cache locations
@@ -23,8 +28,31 @@ impl fmt::Display for ScanSpeed {
     }
 }
 
+/// How much could be lost by deleting an item. Variants are declared from
+/// least to most risky and the derived ordering follows suit: `clean` keeps
+/// items with `risk_level <= max_risk`, so new levels must be inserted in
+/// order.
+///
+/// ```
+/// use cleanser::types::RiskLevel;
+///
+/// assert!(RiskLevel::Safe < RiskLevel::Moderate);
+/// assert!(RiskLevel::Moderate < RiskLevel::Risky);
+/// assert!(RiskLevel::Risky < RiskLevel::Manual);
+/// ```
 #[derive(
-    Debug, Clone, Copy, ValueEnum, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash,
+    Debug,
+    Clone,
+    Copy,
+    ValueEnum,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
 )]
 #[serde(rename_all = "lowercase")]
 pub enum RiskLevel {
@@ -34,6 +62,23 @@ pub enum RiskLevel {
     Moderate,
     /// Higher risk - large files, duplicates, requires review
     Risky,
+    /// Personal files (large files in Documents, Desktop, ...) - only cleaned
+    /// with `--risk manual`
+    Manual,
+}
+
+impl RiskLevel {
+    pub const ALL: &'static [RiskLevel] = &[
+        RiskLevel::Safe,
+        RiskLevel::Moderate,
+        RiskLevel::Risky,
+        RiskLevel::Manual,
+    ];
+
+    /// Every level, safest first
+    pub fn all() -> impl Iterator<Item = RiskLevel> {
+        RiskLevel::ALL.iter().copied()
+    }
 }
 
 impl fmt::Display for RiskLevel {
@@ -42,20 +87,107 @@ impl fmt::Display for RiskLevel {
             RiskLevel::Safe => write!(f, "safe"),
             RiskLevel::Moderate => write!(f, "moderate"),
             RiskLevel::Risky => write!(f, "risky"),
+            RiskLevel::Manual => write!(f, "manual"),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Largest items first
+    Size,
+    /// Least recently modified items first
+    Age,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum GroupBy {
+    /// Risk level, then category within each level
+    Risk,
+    /// Category alone
+    Category,
+    /// Directory a couple of levels below the scanned path
+    Path,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Color when stdout is a terminal and `NO_COLOR` isn't set
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Human-readable report grouped by risk and category
+    Pretty,
+    /// The full results as JSON
+    Json,
+    /// One row per item, with a header row
+    Csv,
+    /// `du -h` style lines: size, a tab, then the path
+    Du,
+    /// Aligned columns of size, risk, category and path, with long paths
+    /// shortened to fit the terminal
+    Table,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CleanableItem {
     pub path: String,
+    /// For an item standing for many scattered files (every `.DS_Store`
+    /// under a scan path, say): the files themselves, which are what gets
+    /// deleted. `path` is then only a label. Empty for an ordinary item.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
     pub size: u64,
+    /// Whether `size` was extrapolated from a partial walk (`--estimate`)
+    #[serde(default)]
+    pub estimated: bool,
+    /// Apparent size (sum of file lengths) when it differs noticeably from the
+    /// on-disk `size`, e.g. for compressed or sparse files
+    #[serde(default)]
+    pub apparent_size: Option<u64>,
+    /// Size including what symlinks inside point to, set by
+    /// `--dereference-size` when it differs from `size`. Deleting the item
+    /// only frees `size`; the targets are never touched.
+    #[serde(default)]
+    pub linked_size: Option<u64>,
     pub category: CleanCategory,
     pub risk_level: RiskLevel,
     pub description: String,
+    /// Last modification time in seconds since the Unix epoch
+    #[serde(default)]
+    pub last_modified: Option<u64>,
+    /// Name of the project a build artifact belongs to
+    #[serde(default)]
+    pub project: Option<String>,
+    /// Which rule flagged the item, e.g. the pattern or marker file it matched
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl CleanableItem {
+    /// The paths deleting the item removes: its members, or else its path
+    pub fn targets(&self) -> Vec<&str> {
+        if self.members.is_empty() {
+            vec![self.path.as_str()]
+        } else {
+            self.members.iter().map(String::as_str).collect()
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+/// Category of a cleanable item.
+///
+/// Categories serialize as stable snake_case names (see [`CleanCategory::machine_name`])
+/// which are the same tokens accepted by `clean --category`. The `Display` form is
+/// for humans and may change. [`CleanCategory::Custom`] carries a free-text label
+/// (e.g. from a config file rule) and serializes as `{"custom": "<label>"}`.
+#[derive(
+    Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[serde(rename_all = "snake_case")]
 pub enum CleanCategory {
     SystemCache,
@@ -64,13 +196,22 @@ pub enum CleanCategory {
     SystemLogs,
     AppLogs,
     TempFiles,
+    Trash,
+    OldDownloads,
     NodeModules,
     BuildArtifacts,
     PipCache,
     BrewCache,
     CargoCache,
+    XcodeSimulators,
+    DockerData,
+    DeviceBackups,
+    /// Photos, Final Cut and iMovie libraries, reported whole
+    MediaLibraries,
     LargeFiles,
     DuplicateFiles,
+    /// A user-labelled category; items with the same label group together
+    Custom(String),
 }
 
 impl fmt::Display for CleanCategory {
@@ -82,31 +223,529 @@ impl fmt::Display for CleanCategory {
             CleanCategory::SystemLogs => write!(f, "System Logs"),
             CleanCategory::AppLogs => write!(f, "Application Logs"),
             CleanCategory::TempFiles => write!(f, "Temporary Files"),
+            CleanCategory::Trash => write!(f, "Trash"),
+            CleanCategory::OldDownloads => write!(f, "Old Downloads"),
             CleanCategory::NodeModules => write!(f, "Node Modules"),
             CleanCategory::BuildArtifacts => write!(f, "Build Artifacts"),
             CleanCategory::PipCache => write!(f, "Pip Cache"),
             CleanCategory::BrewCache => write!(f, "Homebrew Cache"),
             CleanCategory::CargoCache => write!(f, "Cargo Cache"),
+            CleanCategory::XcodeSimulators => write!(f, "Xcode Simulators"),
+            CleanCategory::DockerData => write!(f, "Docker Data"),
+            CleanCategory::DeviceBackups => write!(f, "iOS Device Backups"),
+            CleanCategory::MediaLibraries => write!(f, "Media Libraries"),
             CleanCategory::LargeFiles => write!(f, "Large Files"),
             CleanCategory::DuplicateFiles => write!(f, "Duplicate Files"),
+            CleanCategory::Custom(label) => write!(f, "{}", label),
         }
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl CleanCategory {
+    /// Every built-in category (custom categories can't be enumerated)
+    pub const ALL: &'static [CleanCategory] = &[
+        CleanCategory::SystemCache,
+        CleanCategory::BrowserCache,
+        CleanCategory::AppCache,
+        CleanCategory::SystemLogs,
+        CleanCategory::AppLogs,
+        CleanCategory::TempFiles,
+        CleanCategory::Trash,
+        CleanCategory::OldDownloads,
+        CleanCategory::NodeModules,
+        CleanCategory::BuildArtifacts,
+        CleanCategory::PipCache,
+        CleanCategory::BrewCache,
+        CleanCategory::CargoCache,
+        CleanCategory::XcodeSimulators,
+        CleanCategory::DockerData,
+        CleanCategory::DeviceBackups,
+        CleanCategory::MediaLibraries,
+        CleanCategory::LargeFiles,
+        CleanCategory::DuplicateFiles,
+    ];
+
+    /// Stable identifier, identical to the serialized JSON value for built-in
+    /// categories. A custom category is `custom:<label>`.
+    pub fn machine_name(&self) -> Cow<'static, str> {
+        Cow::Borrowed(match self {
+            CleanCategory::SystemCache => "system_cache",
+            CleanCategory::BrowserCache => "browser_cache",
+            CleanCategory::AppCache => "app_cache",
+            CleanCategory::SystemLogs => "system_logs",
+            CleanCategory::AppLogs => "app_logs",
+            CleanCategory::TempFiles => "temp_files",
+            CleanCategory::Trash => "trash",
+            CleanCategory::OldDownloads => "old_downloads",
+            CleanCategory::NodeModules => "node_modules",
+            CleanCategory::BuildArtifacts => "build_artifacts",
+            CleanCategory::PipCache => "pip_cache",
+            CleanCategory::BrewCache => "brew_cache",
+            CleanCategory::CargoCache => "cargo_cache",
+            CleanCategory::XcodeSimulators => "xcode_simulators",
+            CleanCategory::DockerData => "docker_data",
+            CleanCategory::DeviceBackups => "device_backups",
+            CleanCategory::MediaLibraries => "media_libraries",
+            CleanCategory::LargeFiles => "large_files",
+            CleanCategory::DuplicateFiles => "duplicate_files",
+            CleanCategory::Custom(label) => return Cow::Owned(format!("custom:{}", label)),
+        })
+    }
+
+    /// Parse a name produced by [`CleanCategory::machine_name`]
+    pub fn from_machine_name(name: &str) -> Option<CleanCategory> {
+        if let Some(label) = name.strip_prefix("custom:") {
+            return Some(CleanCategory::Custom(label.to_string()));
+        }
+        CleanCategory::ALL
+            .iter()
+            .find(|category| category.machine_name() == name)
+            .cloned()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ScanResults {
     pub items: Vec<CleanableItem>,
     pub total_size: u64,
     pub scan_speed: ScanSpeed,
+    /// Whether items were carried forward from a previous scan (`--incremental`)
+    #[serde(default)]
+    pub incremental: bool,
+    /// The directories that were scanned, after expansion and validation
+    #[serde(default)]
+    pub scanned_paths: Vec<String>,
+    /// Large file threshold in MB (0 when large files weren't scanned for)
+    #[serde(default)]
+    pub min_file_size_mb: u64,
+    /// When the scan ran, in seconds since the Unix epoch
+    #[serde(default)]
+    pub timestamp: u64,
+    /// Every set of identical files found by `--find-duplicates`, including
+    /// the copy that isn't listed in `items`
+    #[serde(default)]
+    pub duplicate_groups: Vec<DuplicateGroup>,
+}
+
+impl ScanResults {
+    /// Items at or below `max` risk, in scan order
+    pub fn filter_by_risk(&self, max: RiskLevel) -> Vec<&CleanableItem> {
+        self.items
+            .iter()
+            .filter(|item| item.risk_level <= max)
+            .collect()
+    }
+
+    /// Items grouped by risk level, safest first
+    pub fn group_by_risk(&self) -> BTreeMap<RiskLevel, Vec<&CleanableItem>> {
+        let mut groups: BTreeMap<RiskLevel, Vec<&CleanableItem>> = BTreeMap::new();
+        for item in &self.items {
+            groups.entry(item.risk_level).or_default().push(item);
+        }
+        groups
+    }
+
+    /// Items grouped by category, in declaration order (custom categories
+    /// last, by label)
+    pub fn group_by_category(&self) -> BTreeMap<&CleanCategory, Vec<&CleanableItem>> {
+        group_by_category(&self.items)
+    }
+
+    /// Items grouped by their first `depth` path components below the
+    /// scanned path containing them (below `/` for items found elsewhere)
+    pub fn group_by_path(&self, depth: usize) -> BTreeMap<String, Vec<&CleanableItem>> {
+        let mut groups: BTreeMap<String, Vec<&CleanableItem>> = BTreeMap::new();
+        for item in &self.items {
+            let path = Path::new(&item.path);
+            let base = self
+                .scanned_paths
+                .iter()
+                .map(Path::new)
+                .filter(|base| path.starts_with(base))
+                .max_by_key(|base| base.components().count())
+                .unwrap_or(Path::new("/"));
+            let below = path.strip_prefix(base).unwrap_or(path);
+            let mut key = base.to_path_buf();
+            key.extend(below.components().take(depth));
+            groups
+                .entry(key.to_string_lossy().into_owned())
+                .or_default()
+                .push(item);
+        }
+        groups
+    }
+
+    /// Total size per risk level, safest first
+    pub fn total_by_risk(&self) -> BTreeMap<RiskLevel, u64> {
+        let mut totals = BTreeMap::new();
+        for item in &self.items {
+            *totals.entry(item.risk_level).or_default() += item.size;
+        }
+        totals
+    }
+
+    /// Total size per category, in declaration order (custom categories last,
+    /// by label)
+    pub fn total_by_category(&self) -> BTreeMap<CleanCategory, u64> {
+        let mut totals = BTreeMap::new();
+        for item in &self.items {
+            *totals.entry(item.category.clone()).or_default() += item.size;
+        }
+        totals
+    }
+}
+
+/// Group any selection of items by category, e.g. one risk level's worth,
+/// in declaration order
+pub fn group_by_category<'a>(
+    items: impl IntoIterator<Item = &'a CleanableItem>,
+) -> BTreeMap<&'a CleanCategory, Vec<&'a CleanableItem>> {
+    let mut groups: BTreeMap<&CleanCategory, Vec<&CleanableItem>> = BTreeMap::new();
+    for item in items {
+        groups.entry(&item.category).or_default().push(item);
+    }
+    groups
+}
+
+/// Files with identical contents
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DuplicateGroup {
+    /// XXH3-128 of the contents
+    pub hash: String,
+    /// Length of each copy in bytes
+    pub size: u64,
+    /// All copies; the first is the one `items` treats as the original
+    pub paths: Vec<String>,
+}
+
+/// An item found by two scans whose size differs
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ItemChange {
+    /// The item as of the newer scan
+    pub item: CleanableItem,
+    pub old_size: u64,
+}
+
+impl ItemChange {
+    /// Bytes gained since the older scan (negative when the item shrank)
+    pub fn growth(&self) -> i64 {
+        self.item.size as i64 - self.old_size as i64
+    }
+}
+
+/// Total size of a category in two scans
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CategoryDelta {
+    pub category: CleanCategory,
+    pub old_size: u64,
+    pub new_size: u64,
+}
+
+impl CategoryDelta {
+    /// Bytes gained since the older scan (negative when the category shrank)
+    pub fn delta(&self) -> i64 {
+        self.new_size as i64 - self.old_size as i64
+    }
+}
+
+/// Differences between two scans, matched by path
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ScanDiff {
+    pub old_total: u64,
+    pub new_total: u64,
+    /// Items only in the newer scan
+    pub added: Vec<CleanableItem>,
+    /// Items only in the older scan
+    pub removed: Vec<CleanableItem>,
+    /// Items in both scans with different sizes
+    pub changed: Vec<ItemChange>,
+    /// Categories whose total size changed, largest change first
+    pub categories: Vec<CategoryDelta>,
+}
+
+/// Progress notifications emitted while a scan runs
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    /// A detection phase is starting
+    PhaseStarted(String),
+    /// Something the user should know about that didn't stop the scan
+    Warning(String),
+    /// A detection phase has finished, with where its time went
+    PhaseFinished(PhaseProfile),
+    /// A detector found an item (before nested paths are deduplicated)
+    ItemFound(CleanableItem),
+    /// Duplicate detection is hashing candidate files; sent a few times a
+    /// second until every file is done
+    Hashing {
+        files_done: usize,
+        files_total: usize,
+        bytes_done: u64,
+        bytes_total: u64,
+    },
+    /// Every phase has finished
+    ScanComplete,
+}
+
+/// Timing and work done by one phase of a scan
+#[derive(Debug, Clone)]
+pub struct PhaseProfile {
+    pub phase: String,
+    /// Wall-clock time of the phase
+    pub elapsed: Duration,
+    /// Items the phase found (before nested paths are deduplicated)
+    pub items: usize,
+    pub bytes_found: u64,
+    /// Time spent measuring directory sizes, summed across threads
+    pub sizing: Duration,
+    pub dirs_sized: u64,
+    /// Bytes read to hash duplicate candidates
+    pub bytes_hashed: u64,
+}
+
+/// A detection pass of [`scan`](crate::scanner::scan) that can be switched off
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScanPhase {
+    /// Cache directories, including per-profile browser caches
+    Caches,
+    /// Build artifacts and package manager stores
+    BuildArtifacts,
+    Logs,
+    /// Files above `min_file_size_mb`
+    LargeFiles,
+    /// Duplicate files (also requires `find_duplicates`)
+    Duplicates,
+    TrashAndDownloads,
+    Xcode,
+    Docker,
+    Mail,
+    /// iOS and iPadOS backups made by Finder or iTunes
+    DeviceBackups,
+    /// `.DS_Store` files and Quick Look's thumbnail cache
+    Clutter,
+    /// Rules from the config file (see [`CustomRule`])
+    CustomRules,
+}
+
+impl ScanPhase {
+    pub const ALL: &'static [ScanPhase] = &[
+        ScanPhase::Caches,
+        ScanPhase::BuildArtifacts,
+        ScanPhase::Logs,
+        ScanPhase::LargeFiles,
+        ScanPhase::Duplicates,
+        ScanPhase::TrashAndDownloads,
+        ScanPhase::Xcode,
+        ScanPhase::Docker,
+        ScanPhase::Mail,
+        ScanPhase::DeviceBackups,
+        ScanPhase::Clutter,
+        ScanPhase::CustomRules,
+    ];
+
+    /// Every phase, the default for a scan
+    pub fn all() -> HashSet<ScanPhase> {
+        ScanPhase::ALL.iter().copied().collect()
+    }
+}
+
+/// A bundle of scan settings for a common job. Explicit flags still win.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum ScanPreset {
+    /// Build artifacts (node_modules, target, __pycache__, virtualenvs, ...)
+    /// and Xcode data, searched 8 levels deep
+    Dev,
+    /// Large photo, video and audio files
+    Media,
+    /// Caches, logs and Finder clutter
+    System,
+    /// Every phase (the default)
+    Full,
+}
+
+/// Extensions the `media` preset limits large files to
+pub const MEDIA_EXTENSIONS: &[&str] = &[
+    "mov", "mp4", "m4v", "mkv", "avi", "wmv", "webm", "mts", "mp3", "m4a", "wav", "aiff", "flac",
+    "jpg", "jpeg", "png", "heic", "tif", "tiff", "psd", "dng", "cr2", "cr3", "nef", "arw", "raf",
+];
+
+impl ScanPreset {
+    /// The phases the preset runs. Custom rules from the config file always
+    /// run, since the user asked for them.
+    pub fn phases(self) -> HashSet<ScanPhase> {
+        let phases: &[ScanPhase] = match self {
+            ScanPreset::Dev => &[ScanPhase::BuildArtifacts, ScanPhase::Xcode],
+            ScanPreset::Media => &[ScanPhase::LargeFiles, ScanPhase::Duplicates],
+            ScanPreset::System => &[ScanPhase::Caches, ScanPhase::Logs, ScanPhase::Clutter],
+            ScanPreset::Full => ScanPhase::ALL,
+        };
+        phases
+            .iter()
+            .copied()
+            .chain([ScanPhase::CustomRules])
+            .collect()
+    }
+
+    /// Search depth when `--max-depth` isn't given (None: the speed's default)
+    pub fn max_depth(self) -> Option<usize> {
+        match self {
+            ScanPreset::Dev => Some(8),
+            _ => None,
+        }
+    }
+
+    /// Large file extensions when `--ext` isn't given (empty: any)
+    pub fn extensions(self) -> Vec<String> {
+        match self {
+            ScanPreset::Media => MEDIA_EXTENSIONS.iter().map(|ext| ext.to_string()).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// A user-defined detector, read from `[[rules]]` in the config file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    /// Glob matched against absolute paths inside the scanned paths, after
+    /// expanding `~` and `$VAR` (e.g. `~/.mycompany/tmp-builds` or `**/*.tmp`)
+    pub pattern: String,
+    pub category: CleanCategory,
+    pub risk: RiskLevel,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// A previous scan for an incremental scan to build on
+#[derive(Debug, Clone)]
+pub struct PreviousScan {
+    /// When the previous scan ran, in seconds since the Unix epoch
+    pub timestamp: u64,
+    /// Directories the previous scan covered; only subtrees of these are
+    /// carried forward
+    pub scanned_paths: Vec<String>,
+    pub items: Vec<CleanableItem>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ScanConfig {
     pub speed: ScanSpeed,
+    /// Directories to scan, already expanded (see [`crate::paths::expand_path`])
     pub paths: Vec<String>,
     pub min_file_size_mb: u64,
+    /// Only report large files with one of these extensions, e.g. `dmg` or
+    /// `tar.gz` (all when empty). Matched case-insensitively.
+    pub extensions: Vec<String>,
+    /// Never report large files with these extensions
+    pub excluded_extensions: Vec<String>,
+    pub min_dir_size_mb: u64,
+    /// Only log files larger than this many MB are reported
+    pub log_min_size_mb: u64,
+    /// How many levels below each scan path to search (default from
+    /// `speed`). Every phase that searches for items counts depth the same
+    /// way; phases that check fixed locations (Trash, Xcode, browser caches,
+    /// ...) look there regardless, and a found directory's size always
+    /// covers its whole subtree.
     pub max_depth: Option<usize>,
     pub find_duplicates: bool,
+    /// Only files larger than this many KB are hashed for duplicate detection
+    pub dup_min_size_kb: u64,
+    /// Files hashed at once for duplicate detection. `None` picks one per
+    /// thread of the global rayon pool (at most 8), or one at a time when a
+    /// scan path is on a network volume
+    pub hash_threads: Option<usize>,
+    pub follow_symlinks: bool,
+    pub estimate: bool,
+    /// Measure apparent file lengths instead of disk usage (allocated blocks)
+    pub apparent_size: bool,
+    /// Also measure what symlinks inside directories point to, reported
+    /// separately as [`CleanableItem::linked_size`]
+    pub dereference_size: bool,
+    /// Fail instead of warning when a scan path doesn't exist
+    pub strict_paths: bool,
+    /// Detection phases to run
+    pub enabled_phases: HashSet<ScanPhase>,
+    /// Leave build artifacts alone inside git repositories with uncommitted changes
+    pub skip_dirty_repos: bool,
+    /// Rate build artifacts by their git repository's last commit: Safe when
+    /// abandoned, Risky when active. Runs git once per repository.
+    pub repo_activity: bool,
+    /// Extra detectors evaluated by [`ScanPhase::CustomRules`]
+    pub custom_rules: Vec<CustomRule>,
+    /// Only search top-level directories modified since this scan, carrying
+    /// its items forward for the rest
+    pub previous: Option<PreviousScan>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CleanConfig {
+    pub max_risk: RiskLevel,
+    pub dry_run: bool,
+    pub force_scan: bool,
+    /// Clean the items from this saved `scan --json` output instead of the
+    /// cached scan or a fresh one
+    pub load: Option<PathBuf>,
+    pub yes: bool,
+    /// Prompt for every item instead of once for the whole list
+    pub confirm_each: bool,
+    /// Skip the confirmation prompt when less than this many bytes would be
+    /// freed
+    pub confirm_threshold: Option<u64>,
+    pub sudo: bool,
+    pub json: bool,
+    /// Only clean these categories (all when empty)
+    pub categories: Vec<CleanCategory>,
+    /// Only clean items at or under these paths (all when empty). Each must
+    /// match at least one scanned item.
+    pub paths: Vec<PathBuf>,
+    /// Only clean items whose path, or a directory containing it, matches
+    /// one of these globs (all when empty). `~` and `$VAR` are expanded.
+    pub patterns: Vec<String>,
+    /// Allow deleting critical locations such as `/`, `$HOME` or `~/.ssh`
+    pub allow_critical: bool,
+    /// Reuse a cached scan up to this many seconds old (default 1 hour)
+    pub cache_max_age: Option<u64>,
+    /// Only clean Safe items while running on battery power
+    pub on_battery_safe_only: bool,
+    /// Remove outdated Homebrew versions with `brew cleanup` (they're
+    /// skipped otherwise, since deleting them directly confuses Homebrew)
+    pub apply_brew_cleanup: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CleanFailure {
+    pub path: String,
+    pub error: String,
+    /// Bytes still present at the path after the attempt
+    #[serde(default)]
+    pub residual_size: u64,
+}
+
+/// Outcome of a clean run
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct CleanReport {
+    pub cleaned: usize,
+    pub failed: usize,
+    /// Bytes that actually disappeared, measured after deleting
+    pub space_freed: u64,
+    /// Bytes the deleted paths held right before deleting
+    #[serde(default)]
+    pub expected_space_freed: u64,
+    pub failures: Vec<CleanFailure>,
+    /// Items skipped because they resolve to a critical location
+    #[serde(default)]
+    pub protected: Vec<String>,
+    /// Free space on each volume that held a deleted item, before and after
+    #[serde(default)]
+    pub volumes: Vec<VolumeSpace>,
+}
+
+/// Free space on one volume around a clean, as the filesystem reports it
+/// (so including APFS compression, snapshots and anything else writing)
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VolumeSpace {
+    pub mount_point: String,
+    pub free_before: u64,
+    pub free_after: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -114,3 +753,152 @@ pub struct FileHash {
     pub hash: String,
     pub size: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn machine_names_round_trip_for_every_category() {
+        let mut names = HashSet::new();
+        for category in CleanCategory::ALL {
+            let name = category.machine_name();
+            assert!(names.insert(name.clone()), "{} used twice", name);
+            assert_eq!(
+                CleanCategory::from_machine_name(&name).as_ref(),
+                Some(category)
+            );
+            assert_eq!(
+                serde_json::to_string(category).unwrap(),
+                format!("\"{}\"", name)
+            );
+        }
+
+        let custom = CleanCategory::Custom("Old VMs".to_string());
+        assert_eq!(custom.machine_name(), "custom:Old VMs");
+        assert_eq!(
+            CleanCategory::from_machine_name(&custom.machine_name()),
+            Some(custom)
+        );
+        assert_eq!(CleanCategory::from_machine_name("no_such_category"), None);
+    }
+
+    fn item(
+        path: &str,
+        category: CleanCategory,
+        risk_level: RiskLevel,
+        size: u64,
+    ) -> CleanableItem {
+        CleanableItem {
+            path: path.to_string(),
+            members: Vec::new(),
+            size,
+            estimated: false,
+            apparent_size: None,
+            linked_size: None,
+            category,
+            risk_level,
+            description: String::new(),
+            last_modified: None,
+            project: None,
+            reason: None,
+        }
+    }
+
+    fn results(items: Vec<CleanableItem>) -> ScanResults {
+        ScanResults {
+            total_size: items.iter().map(|item| item.size).sum(),
+            items,
+            scan_speed: ScanSpeed::Normal,
+            incremental: false,
+            scanned_paths: vec!["/home/me".to_string()],
+            min_file_size_mb: 0,
+            timestamp: 0,
+            duplicate_groups: Vec::new(),
+        }
+    }
+
+    fn paths<'a>(items: &[&'a CleanableItem]) -> Vec<&'a str> {
+        items.iter().map(|item| item.path.as_str()).collect()
+    }
+
+    #[test]
+    fn filters_and_groups_split_results() {
+        let results = results(vec![
+            item("/home/me/a", CleanCategory::SystemCache, RiskLevel::Safe, 1),
+            item(
+                "/home/me/code/p/node_modules",
+                CleanCategory::NodeModules,
+                RiskLevel::Moderate,
+                2,
+            ),
+            item(
+                "/home/me/b",
+                CleanCategory::SystemCache,
+                RiskLevel::Risky,
+                4,
+            ),
+            item(
+                "/var/log/x.log",
+                CleanCategory::SystemLogs,
+                RiskLevel::Moderate,
+                8,
+            ),
+        ]);
+
+        assert_eq!(
+            paths(&results.filter_by_risk(RiskLevel::Safe)),
+            ["/home/me/a"]
+        );
+        assert_eq!(
+            paths(&results.filter_by_risk(RiskLevel::Moderate)),
+            [
+                "/home/me/a",
+                "/home/me/code/p/node_modules",
+                "/var/log/x.log"
+            ]
+        );
+        assert_eq!(results.filter_by_risk(RiskLevel::Manual).len(), 4);
+
+        let by_risk = results.group_by_risk();
+        assert_eq!(
+            by_risk.keys().copied().collect::<Vec<_>>(),
+            [RiskLevel::Safe, RiskLevel::Moderate, RiskLevel::Risky]
+        );
+        assert_eq!(paths(&by_risk[&RiskLevel::Risky]), ["/home/me/b"]);
+
+        let by_category = results.group_by_category();
+        assert_eq!(
+            paths(&by_category[&CleanCategory::SystemCache]),
+            ["/home/me/a", "/home/me/b"]
+        );
+        assert_eq!(results.total_by_category()[&CleanCategory::SystemCache], 5);
+        assert_eq!(results.total_by_risk()[&RiskLevel::Moderate], 10);
+
+        // Two levels below the scanned path, or below / outside it
+        let by_path = results.group_by_path(2);
+        assert_eq!(
+            by_path.keys().map(String::as_str).collect::<Vec<_>>(),
+            ["/home/me/a", "/home/me/b", "/home/me/code/p", "/var/log"]
+        );
+    }
+
+    #[test]
+    fn custom_categories_group_by_label() {
+        let a = || CleanCategory::Custom("A".to_string());
+        let b = CleanCategory::Custom("B".to_string());
+        let results = results(vec![
+            item("/home/me/one", a(), RiskLevel::Safe, 1),
+            item("/home/me/two", b.clone(), RiskLevel::Safe, 2),
+            item("/home/me/three", a(), RiskLevel::Safe, 4),
+        ]);
+
+        let groups = results.group_by_category();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(paths(&groups[&a()]), ["/home/me/one", "/home/me/three"]);
+        assert_eq!(paths(&groups[&b]), ["/home/me/two"]);
+        assert_eq!(results.total_by_category()[&a()], 5);
+        assert_eq!(a().to_string(), "A");
+        assert_eq!(b.to_string(), "B");
+    }
+}